@@ -0,0 +1,30 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// Returned by the `requested_files` export to tell the linter which files in the directory the
+/// plugin lints.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Files {
+    /// Extensions of the files, e.g. `yaml`. Every file is requested when it is empty.
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    /// Names of the directories that are skipped, e.g. `.terraform`.
+    #[serde(default)]
+    pub ignored_dirs: Vec<String>,
+}
+
+/// The requested files, passed to the `new` export.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AvailableFiles {
+    pub files: Vec<FilteredFile>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilteredFile {
+    /// File name of the file.
+    pub name: String,
+    /// Path of the file relative to the linted directory.
+    pub path: PathBuf,
+    pub contents: String,
+}
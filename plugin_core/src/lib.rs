@@ -1,12 +1,17 @@
 mod errors;
+mod files;
 mod lint;
+mod location;
+mod metadata;
+mod suppression;
 mod utils;
 
 pub use lint::{Lint, LintResult};
+pub use location::{find_location, SourceLocation};
 
 pub use errors::PluginErrors;
+pub use files::{AvailableFiles, Files, FilteredFile};
+pub use metadata::{PluginMetadata, PLUGIN_API_VERSION};
+pub use suppression::Suppression;
 pub use trustfall;
-pub use utils::{
-    convert_to_args, find_files, find_files_ignore_dir, from_field_value, from_json_value,
-    open_file,
-};
+pub use utils::{convert_to_args, from_field_value, from_json_value};
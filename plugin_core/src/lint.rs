@@ -13,6 +13,7 @@ pub struct Lint {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum LintResult {
+    Info(String),
     Warning(String),
     Error(String),
 }
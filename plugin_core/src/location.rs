@@ -0,0 +1,87 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// Where a block of the linted sources is, e.g. a Terraform module or an OpenAPI operation, so that
+/// the rows of a lint can be shown at the file and line they came from.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SourceLocation {
+    /// Values identifying the block, matched against the outputs of a row with the same names.
+    pub fields: serde_json::Map<String, serde_json::Value>,
+    /// Path of the file relative to the linted directory.
+    pub path: PathBuf,
+    /// Line of the block in the file, starting at 1.
+    pub line: u32,
+}
+
+impl SourceLocation {
+    /// Number of the fields identifying the row, or 0 when the row is not from the block.
+    pub fn matches(&self, row: &serde_json::Value) -> usize {
+        matching_fields(&self.fields, row)
+    }
+}
+
+/// Number of the fields the row has the same output for, or 0 when any of the row's outputs for
+/// the fields has a different value.
+fn matching_fields(
+    fields: &serde_json::Map<String, serde_json::Value>,
+    row: &serde_json::Value,
+) -> usize {
+    let Some(row) = row.as_object() else {
+        return 0;
+    };
+    let mut matched = 0;
+    for (key, value) in fields {
+        match row.get(key) {
+            Some(row_value) if row_value == value => matched += 1,
+            Some(_) => return 0,
+            None => {}
+        }
+    }
+    matched
+}
+
+/// The location of the block the row is from, preferring the locations identified by more
+/// fields, e.g. an operation over its path.
+pub fn find_location<'a>(
+    locations: &'a [SourceLocation],
+    row: &serde_json::Value,
+) -> Option<&'a SourceLocation> {
+    let mut best: Option<(usize, &SourceLocation)> = None;
+    for location in locations {
+        let matched = location.matches(row);
+        if matched > 0 && best.is_none_or(|(best_matched, _)| matched > best_matched) {
+            best = Some((matched, location));
+        }
+    }
+    best.map(|(_, location)| location)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn location(fields: serde_json::Value, line: u32) -> SourceLocation {
+        SourceLocation {
+            fields: fields.as_object().unwrap().clone(),
+            path: PathBuf::from("openapi.yaml"),
+            line,
+        }
+    }
+
+    #[test]
+    fn test_find_location() {
+        let locations = vec![
+            location(json!({"path": "/pets"}), 3),
+            location(json!({"path": "/pets", "method": "GET"}), 4),
+            location(json!({"path": "/pets", "method": "POST"}), 8),
+        ];
+        let line = |row| find_location(&locations, &row).map(|location| location.line);
+        assert_eq!(line(json!({"path": "/pets", "method": "POST"})), Some(8));
+        assert_eq!(line(json!({"path": "/pets"})), Some(3));
+        assert_eq!(line(json!({"path": "/pets", "method": "DELETE"})), Some(3));
+        assert_eq!(line(json!({"name": "service_api"})), None);
+    }
+}
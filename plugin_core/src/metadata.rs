@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// Version of the interface between the linter and its plugins: the exports the linter calls and
+/// the shape of the [`crate::Lint`] and results passed through them. It is incremented whenever a
+/// change needs plugins to be rebuilt.
+pub const PLUGIN_API_VERSION: u32 = 3;
+
+/// Returned by the `metadata` export every plugin has to have, so that the linter can check that
+/// it is able to run the plugin before calling any other export.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginMetadata {
+    pub name: String,
+    pub version: String,
+    /// The [`PLUGIN_API_VERSION`] the plugin was built with.
+    pub api_version: u32,
+    pub description: String,
+    /// Extensions of the files the plugin lints, e.g. `yaml` or `tf`.
+    pub file_types: Vec<String>,
+}
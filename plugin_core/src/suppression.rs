@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// Lints ignored for a single block of the linted sources, e.g. with a `# linter:ignore` comment
+/// above a Terraform block or an `x-linter-ignore` extension on an OpenAPI operation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Suppression {
+    /// Names of the lints that are ignored.
+    pub lints: Vec<String>,
+    /// Values identifying the block, e.g. the path and method of an operation. Rows are matched
+    /// against the outputs with the same names.
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+impl Suppression {
+    /// Whether the row of the lint belongs to the ignored block. The row needs an output with the
+    /// same value for every field, so that e.g. an ignored operation does not suppress the rows of
+    /// the other operations on its path.
+    pub fn matches(&self, lint_name: &str, row: &serde_json::Value) -> bool {
+        let Some(row) = row.as_object() else {
+            return false;
+        };
+        self.lints.iter().any(|lint| lint == lint_name)
+            && !self.fields.is_empty()
+            && self
+                .fields
+                .iter()
+                .all(|(key, value)| row.get(key) == Some(value))
+    }
+}
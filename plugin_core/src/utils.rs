@@ -1,33 +1,9 @@
 use std::{
     collections::{BTreeMap, HashMap},
-    ffi::OsStr,
-    io::Read,
-    path::PathBuf,
     sync::Arc,
 };
 use trustfall::FieldValue;
 
-pub fn find_files(path: &std::path::Path, extension: &OsStr) -> Vec<PathBuf> {
-    let mut files = Vec::new();
-    for entry in path.read_dir().expect("Failed to read directory").flatten() {
-        if entry.path().is_dir() {
-            files.append(&mut find_files(&entry.path(), extension));
-        } else if entry.path().extension() == Some(extension) {
-            files.push(entry.path());
-        }
-    }
-    files
-}
-
-/// Gets a file's contents
-pub fn open_file(filename: PathBuf) -> String {
-    let mut file = std::fs::File::open(filename).expect("Couldn't find or open the file");
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)
-        .expect("Couldn't read the contents of the file");
-    contents
-}
-
 pub fn from_json_value(value: &serde_json::Value) -> FieldValue {
     match value {
         serde_json::Value::Null => FieldValue::Null,
@@ -85,20 +61,3 @@ pub fn from_field_value(value: &FieldValue) -> serde_json::Value {
         _ => todo!(),
     }
 }
-
-pub fn find_files_ignore_dir(path: PathBuf, extension: &str, folder: &str) -> Vec<PathBuf> {
-    let mut files = Vec::new();
-    for entry in path
-        .read_dir()
-        .expect("Failed to get dir contents")
-        .flatten()
-    {
-        if entry.path().is_dir() && !entry.path().ends_with(folder) {
-            files.extend(find_files_ignore_dir(entry.path(), extension, folder));
-        } else if entry.path().is_file() && entry.path().extension() == Some(OsStr::new(extension))
-        {
-            files.push(entry.path().clone());
-        }
-    }
-    files
-}
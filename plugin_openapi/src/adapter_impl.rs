@@ -1,6 +1,6 @@
 use std::sync::{Arc, OnceLock};
 
-use plugin_core::{find_files, open_file, PluginErrors};
+use plugin_core::{AvailableFiles, PluginErrors, SourceLocation, Suppression};
 use trustfall::{
     provider::{
         resolve_coercion_using_schema, resolve_property_with, AsVertex, ContextIterator,
@@ -11,7 +11,7 @@ use trustfall::{
 };
 
 use super::{
-    utils::{merge, Route},
+    utils::{extract_path_lines, merge, Route},
     vertex::Vertex,
 };
 
@@ -21,6 +21,7 @@ static SCHEMA: OnceLock<Schema> = OnceLock::new();
 #[derive(Debug, Default)]
 pub struct OpenApiAdapter {
     openapi: openapiv3::OpenAPI,
+    locations: Vec<SourceLocation>,
 }
 
 impl OpenApiAdapter {
@@ -30,26 +31,66 @@ impl OpenApiAdapter {
         SCHEMA.get_or_init(|| Schema::parse(Self::SCHEMA_TEXT).expect("not a valid schema"))
     }
 
-    pub fn new() -> Result<Self, PluginErrors> {
-        let path = std::path::Path::new("contents");
-        let openapi = if path.is_dir() {
-            let mut files = find_files(path, "yaml".as_ref());
-            files.extend(find_files(path, "yml".as_ref()));
-            let mut files_content = Vec::new();
-            for file in files {
-                files_content.push(open_file(file));
+    /// Merges the OpenAPI files into a single document.
+    pub fn new(files: AvailableFiles) -> Result<Self, PluginErrors> {
+        if files.files.is_empty() {
+            return Err(PluginErrors::FilesNotFound(
+                "OpenAPI yaml files".to_string(),
+            ));
+        }
+        let mut locations = Vec::new();
+        for file in &files.files {
+            for (path, method, line) in extract_path_lines(&file.contents) {
+                let mut fields = serde_json::Map::new();
+                fields.insert("path".to_string(), serde_json::Value::String(path));
+                if let Some(method) = method {
+                    fields.insert("method".to_string(), serde_json::Value::String(method));
+                }
+                locations.push(SourceLocation {
+                    fields,
+                    path: file.path.clone(),
+                    line,
+                });
             }
-            let merged_content = merge(files_content)?;
-            serde_yaml::from_str(&merged_content).unwrap()
-        } else if path.is_file() {
-            let file = std::fs::File::open(path).expect("failed to open file");
-            let reader = std::io::BufReader::new(file);
-            serde_yaml::from_reader(reader).expect("failed to parse OpenAPI file")
-        } else {
-            panic!("Path: {:?} is not a file or directory", path)
-        };
-
-        Ok(Self { openapi })
+        }
+        let merged_content = merge(files.files.into_iter().map(|file| file.contents).collect())?;
+        let openapi = serde_yaml::from_str(&merged_content)
+            .map_err(|e| PluginErrors::PluginError(e.to_string()))?;
+
+        Ok(Self { openapi, locations })
+    }
+
+    /// The operations with lints ignored by the `x-linter-ignore` extension, identified by their
+    /// path and method.
+    pub fn suppressions(&self) -> Vec<Suppression> {
+        let mut suppressions = Vec::new();
+        for (path, item) in self.openapi.paths.paths.iter() {
+            let route: Route = item.clone().into();
+            for operation in route.operations() {
+                if operation.ignored_lints.is_empty() {
+                    continue;
+                }
+                suppressions.push(Suppression {
+                    lints: operation.ignored_lints.clone(),
+                    fields: [
+                        ("path".to_string(), serde_json::Value::from(path.as_str())),
+                        (
+                            "method".to_string(),
+                            serde_json::Value::from(operation.method.as_str()),
+                        ),
+                    ]
+                    .into_iter()
+                    .collect(),
+                });
+            }
+        }
+        suppressions
+    }
+
+    /// The file and line of each path, identified by the path, and of each operation, identified
+    /// by its path and method.
+    pub fn locations(&self) -> Vec<SourceLocation> {
+        self.locations.clone()
     }
 
     fn info(&self) -> Vertex {
@@ -66,25 +107,20 @@ impl OpenApiAdapter {
             .clone()
             .into();
         route.path = path.to_string();
-        Vertex::Path(route)
+        Vertex::Path(Box::new(route))
     }
 
     fn paths<'a>(&self) -> VertexIterator<'a, Vertex> {
-        let iter = self.openapi.paths.clone().into_iter().filter_map(|x| {
+        let iter = self.openapi.paths.clone().into_iter().map(|x| {
             let mut route: Route = x.1.into();
             route.path = x.0.clone().to_string();
-            Some(Vertex::Path(route))
+            Vertex::Path(Box::new(route))
         });
         Box::new(iter)
     }
 
     fn tags<'a>(&self) -> VertexIterator<'a, Vertex> {
-        let iter = self
-            .openapi
-            .tags
-            .clone()
-            .into_iter()
-            .filter_map(|x| Some(Vertex::Tag(x)));
+        let iter = self.openapi.tags.clone().into_iter().map(Vertex::Tag);
         Box::new(iter)
         // Vertex::Tags(self.openapi.tags.clone())
     }
@@ -2,8 +2,10 @@ use std::sync::{Arc, OnceLock};
 use trustfall::{execute_query, Schema};
 
 use extism_pdk::*;
-use plugin_core::{convert_to_args, from_field_value, Lint, PluginErrors};
-use serde::{Deserialize, Serialize};
+use plugin_core::{
+    convert_to_args, from_field_value, AvailableFiles, Files, Lint, PluginErrors, PluginMetadata,
+    SourceLocation, Suppression, PLUGIN_API_VERSION,
+};
 
 mod adapter_impl;
 mod edges;
@@ -17,22 +19,15 @@ mod tests;
 pub use adapter_impl::OpenApiAdapter;
 pub use vertex::Vertex;
 
-#[derive(Debug, Default, Serialize, Deserialize)]
-pub struct AvailableFiles {
-    pub files: Vec<FilteredFile>,
-}
-
-#[derive(Debug, Default, Serialize, Deserialize)]
-pub struct FilteredFile {
-    pub name: String,
-    pub path: std::path::PathBuf,
-    pub contents: String,
-}
-
-#[derive(Debug, Default, Serialize, Deserialize)]
-pub struct Files {
-    extensions: Vec<String>,
-    ignored_dirs: Vec<String>,
+#[plugin_fn]
+pub fn metadata() -> FnResult<Json<PluginMetadata>> {
+    Ok(Json(PluginMetadata {
+        name: String::from("openapi"),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        api_version: PLUGIN_API_VERSION,
+        description: String::from("Lints OpenAPI documents"),
+        file_types: vec![String::from("yml"), String::from("yaml")],
+    }))
 }
 
 #[plugin_fn]
@@ -47,13 +42,30 @@ pub fn requested_files() -> FnResult<Json<Files>> {
 static ADAPTER: OnceLock<Arc<OpenApiAdapter>> = OnceLock::new();
 
 #[plugin_fn]
-pub fn new() -> FnResult<()> {
-    let s = OpenApiAdapter::new()?;
+pub fn new(Json(files): Json<AvailableFiles>) -> FnResult<()> {
+    let s = OpenApiAdapter::new(files)?;
     ADAPTER.get_or_init(|| Arc::new(s));
 
     Ok(())
 }
 
+#[plugin_fn]
+pub fn schema() -> FnResult<String> {
+    Ok(OpenApiAdapter::SCHEMA_TEXT.to_string())
+}
+
+#[plugin_fn]
+pub fn suppressions() -> FnResult<Json<Vec<Suppression>>> {
+    let adapter = ADAPTER.get().expect("adapter not initialized");
+    Ok(Json(adapter.suppressions()))
+}
+
+#[plugin_fn]
+pub fn locations() -> FnResult<Json<Vec<SourceLocation>>> {
+    let adapter = ADAPTER.get().expect("adapter not initialized");
+    Ok(Json(adapter.locations()))
+}
+
 #[plugin_fn]
 pub fn lint_all(Json(lints): Json<Vec<Lint>>) -> FnResult<()> {
     let adapter = ADAPTER.get().expect("adapter not initialized").clone();
@@ -64,6 +76,9 @@ pub fn lint_all(Json(lints): Json<Vec<Lint>>) -> FnResult<()> {
             Ok(terraform_lint) => {
                 if !terraform_lint.is_empty() {
                     match lint.output {
+                        plugin_core::LintResult::Info(ref message) => {
+                            info!("{}", message);
+                        }
                         plugin_core::LintResult::Warning(ref message) => {
                             warn!("{}", message);
                         }
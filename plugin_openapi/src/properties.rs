@@ -78,6 +78,9 @@ pub(super) fn resolve_operation_property<'a, V: AsVertex<Vertex> + 'a>(
         "description" => {
             resolve_property_with(contexts, field_property!(as_operation, description))
         }
+        "ignoredLints" => {
+            resolve_property_with(contexts, field_property!(as_operation, ignored_lints))
+        }
         "summary" => resolve_property_with(contexts, field_property!(as_operation, summary)),
         "method" => resolve_property_with(contexts, field_property!(as_operation, method)),
         "tags" => resolve_property_with(contexts, field_property!(as_operation, tags)),
@@ -1,6 +1,6 @@
 use trustfall::provider::check_adapter_invariants;
 
-use super::OpenApiAdapter;
+use super::{utils::extract_path_lines, OpenApiAdapter};
 
 #[test]
 fn adapter_satisfies_trustfall_invariants() {
@@ -8,3 +8,38 @@ fn adapter_satisfies_trustfall_invariants() {
     let schema = OpenApiAdapter::schema();
     check_adapter_invariants(schema, adapter);
 }
+
+#[test]
+fn paths_and_operations_are_located() {
+    let contents = r#"openapi: 3.0.0
+info:
+  title: Pets
+  version: 1.0.0
+paths:
+  /pets:
+    get:
+      summary: List pets
+      responses:
+        '200':
+          description: The pets
+    post:
+      summary: Add a pet
+  '/pets/{id}':
+    # Deletes a pet
+    delete:
+      parameters:
+        - name: get
+components:
+  schemas: {}
+"#;
+    assert_eq!(
+        extract_path_lines(contents),
+        vec![
+            ("/pets".to_string(), None, 6),
+            ("/pets".to_string(), Some("GET".to_string()), 7),
+            ("/pets".to_string(), Some("POST".to_string()), 12),
+            ("/pets/{id}".to_string(), None, 14),
+            ("/pets/{id}".to_string(), Some("DELETE".to_string()), 16),
+        ]
+    );
+}
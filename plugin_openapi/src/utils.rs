@@ -15,6 +15,50 @@ pub fn merge(files_contents: Vec<String>) -> Result<String, PluginErrors> {
     Ok(hash.to_string())
 }
 
+const METHODS: [&str; 8] = [
+    "get", "post", "put", "delete", "patch", "options", "head", "trace",
+];
+
+/// Finds the paths and their operations in an OpenAPI yaml file and returns the path, the method
+/// in upper case for operations, and the line, starting at 1, of each of them.
+pub fn extract_path_lines(contents: &str) -> Vec<(String, Option<String>, u32)> {
+    let mut lines = Vec::new();
+    let mut in_paths = false;
+    let mut path: Option<(String, usize)> = None;
+    let mut method_indent = None;
+    for (index, line) in contents.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        if indent == 0 {
+            in_paths = trimmed == "paths:";
+            path = None;
+            continue;
+        }
+        if !in_paths {
+            continue;
+        }
+        let Some(key) = trimmed.strip_suffix(':') else {
+            continue;
+        };
+        let key = key.trim_matches(|c| c == '\'' || c == '"');
+        if let Some((path, path_indent)) = &path
+            && indent > *path_indent
+        {
+            if *method_indent.get_or_insert(indent) == indent && METHODS.contains(&key) {
+                lines.push((path.clone(), Some(key.to_uppercase()), index as u32 + 1));
+            }
+        } else if key.starts_with('/') {
+            path = Some((key.to_string(), indent));
+            method_indent = None;
+            lines.push((key.to_string(), None, index as u32 + 1));
+        }
+    }
+    lines
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Route {
     pub path: String,
@@ -45,6 +89,22 @@ impl From<openapiv3::ReferenceOr<PathItem>> for Route {
     }
 }
 
+impl Route {
+    /// The operations that are set on the path.
+    pub fn operations(&self) -> impl Iterator<Item = &Operator> {
+        [
+            &self.get,
+            &self.post,
+            &self.put,
+            &self.delete,
+            &self.patch,
+            &self.options,
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Operator {
     pub method: String,
@@ -52,6 +112,9 @@ pub struct Operator {
     pub description: Option<String>,
     pub tags: Option<Vec<String>>,
     pub aws: Option<AmazonApigatewayIntegration>,
+    /// Lints ignored for the operation with the `x-linter-ignore` extension.
+    #[serde(default)]
+    pub ignored_lints: Vec<String>,
 }
 
 impl Operator {
@@ -80,6 +143,17 @@ impl Operator {
                 }
                 None => None,
             },
+            ignored_lints: match operation.extensions.get("x-linter-ignore") {
+                Some(serde_json::Value::String(lint)) => vec![lint.clone()],
+                Some(value) => match serde_json::from_value::<Vec<String>>(value.clone()) {
+                    Ok(lints) => lints,
+                    Err(e) => {
+                        error!("Failed to deserialize the x-linter-ignore extension: {e} {value}");
+                        Vec::new()
+                    }
+                },
+                None => Vec::new(),
+            },
         }
     }
 }
@@ -1,8 +1,6 @@
-use std::{
-    path::Path,
-    sync::{Arc, OnceLock},
-};
+use std::sync::{Arc, OnceLock};
 
+use plugin_core::{AvailableFiles, PluginErrors, SourceLocation, Suppression};
 use trustfall::{
     provider::{
         resolve_coercion_using_schema, resolve_property_with, AsVertex, ContextIterator,
@@ -33,11 +31,55 @@ impl HclAdapter {
         SCHEMA.get_or_init(|| Schema::parse(Self::SCHEMA_TEXT).expect("not a valid schema"))
     }
 
-    pub fn new(path: &Path) -> Self {
-        Self {
-            data: extract_data_from_hcl(path),
-        }
+    pub fn new(files: AvailableFiles) -> Result<Self, PluginErrors> {
+        Ok(Self {
+            data: extract_data_from_hcl(files)?,
+        })
+    }
+
+    /// The modules with lints ignored by `# linter:ignore` comments.
+    pub fn suppressions(&self) -> Vec<Suppression> {
+        module_suppressions(&self.data.modules)
     }
+
+    /// The file and line of each module, identified by its name.
+    pub fn locations(&self) -> Vec<SourceLocation> {
+        self.data
+            .modules
+            .iter()
+            .filter_map(|module| {
+                let (path, line) = self.data.block_locations.get(&module.address())?;
+                Some(SourceLocation {
+                    fields: [(
+                        "name".to_string(),
+                        serde_json::Value::String(module.name.clone()),
+                    )]
+                    .into_iter()
+                    .collect(),
+                    path: path.clone(),
+                    line: *line,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The modules with ignored lints, identified by their address, e.g. `module.service_api`, so that
+/// the rows of blocks of other types with the same name are not suppressed.
+pub(crate) fn module_suppressions(modules: &[Module]) -> Vec<Suppression> {
+    modules
+        .iter()
+        .filter(|module| !module.ignored_lints.is_empty())
+        .map(|module| Suppression {
+            lints: module.ignored_lints.clone(),
+            fields: [(
+                "address".to_string(),
+                serde_json::Value::String(module.address()),
+            )]
+            .into_iter()
+            .collect(),
+        })
+        .collect()
 }
 
 impl<'a> trustfall::provider::Adapter<'a> for HclAdapter {
@@ -75,22 +117,27 @@ impl<'a> trustfall::provider::Adapter<'a> for HclAdapter {
                     )
                     .as_str();
                 let module_tag = tag;
+                let ignored_lints = &self.data.ignored_lints;
                 let iter: Vec<Vertex> = self
                     .data
                     .raw
                     .clone()
                     .into_iter()
                     .filter_map(move |value| {
-                        if let Some(tag) = module_tag {
+                        let module = if let Some(tag) = module_tag {
                             value
                                 .get(name)
                                 .and_then(|x| x.get(tag))
-                                .map(|v| Vertex::Module(Module::from_serde(v.clone(), tag)))
+                                .map(|v| Module::from_serde(v.clone(), tag))
                         } else {
-                            value
-                                .get(name)
-                                .map(|v| Vertex::Module(Module::from_serde(v.clone(), name)))
-                        }
+                            value.get(name).map(|v| Module::from_serde(v.clone(), name))
+                        };
+                        module.map(|mut module| {
+                            if let Some(lints) = ignored_lints.get(&module.address()) {
+                                module.ignored_lints = lints.clone();
+                            }
+                            Vertex::Module(module)
+                        })
                     })
                     .collect();
                 Box::new(iter.into_iter())
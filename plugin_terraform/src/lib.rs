@@ -8,26 +8,63 @@ pub mod model;
 mod tests;
 pub mod utils;
 
-use std::{
-    path::Path,
-    sync::{Arc, OnceLock},
-};
+use std::sync::{Arc, OnceLock};
 
 pub use adapter_impl::HclAdapter;
 use extism_pdk::{error, plugin_fn, FnResult, Json};
-use plugin_core::{convert_to_args, from_field_value, Lint, PluginErrors};
+use plugin_core::{
+    convert_to_args, from_field_value, AvailableFiles, Files, Lint, PluginErrors, PluginMetadata,
+    SourceLocation, Suppression, PLUGIN_API_VERSION,
+};
 use trustfall::execute_query;
 pub use vertex::Vertex;
 
 static ADAPTER: OnceLock<Arc<HclAdapter>> = OnceLock::new();
 
 #[plugin_fn]
-pub fn new() -> FnResult<()> {
-    ADAPTER.get_or_init(|| Arc::new(HclAdapter::new(Path::new("contents"))));
+pub fn metadata() -> FnResult<Json<PluginMetadata>> {
+    Ok(Json(PluginMetadata {
+        name: String::from("terraform"),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        api_version: PLUGIN_API_VERSION,
+        description: String::from("Lints Terraform configurations"),
+        file_types: vec![String::from("tf")],
+    }))
+}
+
+#[plugin_fn]
+pub fn requested_files() -> FnResult<Json<Files>> {
+    Ok(Json(Files {
+        extensions: vec![String::from("tf")],
+        ignored_dirs: vec![String::from(".terraform")],
+    }))
+}
+
+#[plugin_fn]
+pub fn new(Json(files): Json<AvailableFiles>) -> FnResult<()> {
+    let adapter = HclAdapter::new(files)?;
+    ADAPTER.get_or_init(|| Arc::new(adapter));
 
     Ok(())
 }
 
+#[plugin_fn]
+pub fn schema() -> FnResult<String> {
+    Ok(HclAdapter::SCHEMA_TEXT.to_string())
+}
+
+#[plugin_fn]
+pub fn suppressions() -> FnResult<Json<Vec<Suppression>>> {
+    let adapter = ADAPTER.get().expect("adapter not initialized");
+    Ok(Json(adapter.suppressions()))
+}
+
+#[plugin_fn]
+pub fn locations() -> FnResult<Json<Vec<SourceLocation>>> {
+    let adapter = ADAPTER.get().expect("adapter not initialized");
+    Ok(Json(adapter.locations()))
+}
+
 #[plugin_fn]
 pub fn lint_all(Json(lints): Json<Vec<Lint>>) -> FnResult<()> {
     let adapter = ADAPTER.get().expect("adapter not initialized").clone();
@@ -64,9 +101,9 @@ pub fn lint_single(Json(lint): Json<Lint>) -> FnResult<String> {
     let schema = HclAdapter::schema();
     let variables = convert_to_args(lint.args);
     let mut lint_results = Vec::new();
-    for data_item in execute_query(schema, adapter.clone().to_owned(), &lint.lint, variables)
-        .expect("not a legal query")
-    {
+    let iter = execute_query(schema, adapter.clone().to_owned(), &lint.lint, variables)
+        .map_err(|e| PluginErrors::PluginError(e.to_string()))?;
+    for data_item in iter {
         let transparent: serde_json::Value = data_item
             .into_iter()
             .map(|(k, v)| (k.to_string(), from_field_value(&v)))
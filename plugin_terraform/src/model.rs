@@ -1,3 +1,5 @@
+use std::{collections::HashMap, path::PathBuf};
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -8,6 +10,10 @@ pub struct HCL {
     pub api_config: Option<ApiConfig>,
     pub lambda: Vec<Lambda>,
     pub raw: Vec<Value>,
+    /// Lints ignored with `# linter:ignore` comments per block address, e.g. `module.service_api`.
+    pub ignored_lints: HashMap<String, Vec<String>>,
+    /// File and line of each top level block per block address.
+    pub block_locations: HashMap<String, (PathBuf, u32)>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -17,9 +23,15 @@ pub struct Module {
     pub source: String,
     pub version: String,
     pub variables: Vec<Variable>,
+    /// Lints ignored for the module with `# linter:ignore` comments.
+    pub ignored_lints: Vec<String>,
 }
 
 impl Module {
+    pub fn address(&self) -> String {
+        format!("module.{}", self.name)
+    }
+
     pub fn from_serde(value: serde_json::Value, name: &str) -> Self {
         let source = value["source"].as_str().unwrap().to_string();
         let version = value["version"].as_str().unwrap().to_string();
@@ -37,6 +49,7 @@ impl Module {
             source,
             version,
             variables,
+            ignored_lints: Vec::new(),
         }
     }
 }
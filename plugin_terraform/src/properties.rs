@@ -63,6 +63,16 @@ pub(super) fn resolve_module_property<'a, V: AsVertex<Vertex> + 'a>(
     _resolve_info: &ResolveInfo,
 ) -> ContextOutcomeIterator<'a, V, FieldValue> {
     match property_name {
+        "address" => resolve_property_with(contexts, |vertex| {
+            let module = vertex
+                .as_module()
+                .expect("conversion failed, vertex was not a Module");
+            module.address().into()
+        }),
+        "ignored_lints" => {
+            resolve_property_with(contexts, field_property!(as_module, ignored_lints))
+        }
+        "name" => resolve_property_with(contexts, field_property!(as_module, name)),
         "source" => resolve_property_with(contexts, field_property!(as_module, source)),
         "version" => resolve_property_with(contexts, field_property!(as_module, version)),
         _ => {
@@ -1,6 +1,12 @@
+use serde_json::json;
 use trustfall::provider::check_adapter_invariants;
 
-use super::HclAdapter;
+use super::{
+    adapter_impl::module_suppressions,
+    model::Module,
+    utils::{extract_block_lines, extract_ignored_lints},
+    HclAdapter,
+};
 
 #[test]
 fn adapter_satisfies_trustfall_invariants() {
@@ -8,3 +14,89 @@ fn adapter_satisfies_trustfall_invariants() {
     let schema = HclAdapter::schema();
     check_adapter_invariants(schema, adapter);
 }
+
+#[test]
+fn ignore_comments_are_recorded_per_block() {
+    let contents = r#"
+# linter:ignore "All API endpoints should have tags"
+// linter:ignore "Modules need a version", "Modules need a source"
+module "service_api" {
+  source = "./modules/api"
+}
+
+# linter:ignore "Lambdas need a description"
+
+module "lambda" {
+  source = "./modules/lambda"
+}
+
+# linter:ignore "Buckets need tags"
+resource "aws_s3_bucket" "logs" {
+  bucket = "logs"
+}
+
+# linter:ignore
+"#;
+    let comments = extract_ignored_lints(contents);
+    assert_eq!(comments.blocks.len(), 2);
+    assert_eq!(
+        comments.blocks["module.service_api"],
+        vec![
+            "All API endpoints should have tags",
+            "Modules need a version",
+            "Modules need a source"
+        ]
+    );
+    assert_eq!(
+        comments.blocks["resource.aws_s3_bucket.logs"],
+        vec!["Buckets need tags"]
+    );
+    assert_eq!(comments.detached, vec![8, 19]);
+}
+
+#[test]
+fn suppressions_are_keyed_by_block_address() {
+    let modules = vec![
+        Module {
+            name: "pets".to_string(),
+            ignored_lints: vec!["Modules need a version".to_string()],
+            ..Default::default()
+        },
+        Module {
+            name: "owners".to_string(),
+            ..Default::default()
+        },
+    ];
+    let suppressions = module_suppressions(&modules);
+    assert_eq!(suppressions.len(), 1);
+    let suppression = &suppressions[0];
+    assert!(suppression.matches(
+        "Modules need a version",
+        &json!({"address": "module.pets", "name": "pets"})
+    ));
+    assert!(!suppression.matches("Modules need a version", &json!({"name": "pets"})));
+    assert!(!suppression.matches(
+        "Modules need a version",
+        &json!({"address": "module.owners", "name": "owners"})
+    ));
+}
+
+#[test]
+fn top_level_blocks_are_located() {
+    let contents = r#"terraform {
+  required_version = ">= 1.0"
+}
+
+# A comment {
+module "service_api" {
+  source = "./modules/api"
+  tags = {
+    team = "api"
+  }
+}
+"#;
+    let lines = extract_block_lines(contents);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines["terraform"], 1);
+    assert_eq!(lines["module.service_api"], 6);
+}
@@ -1,118 +1,237 @@
-use std::path::Path;
+use std::collections::HashMap;
 
 // TODO change this to explicit errors
 use anyhow::anyhow;
-use extism_pdk::{debug, error};
-use plugin_core::find_files_ignore_dir;
+use extism_pdk::{debug, error, warn};
+use plugin_core::{AvailableFiles, PluginErrors};
 
 use super::model::{
     ApiConfig, Backend, Lambda, Module, RequiredProvider, TemplateVariable, Terraform, Variable,
     HCL,
 };
 
-pub fn extract_data_from_hcl(path: &Path) -> HCL {
-    let files = find_files_ignore_dir(path.to_path_buf(), "tf", ".terraform");
+pub fn extract_data_from_hcl(files: AvailableFiles) -> Result<HCL, PluginErrors> {
     let mut json = Vec::new();
-    for file in files {
-        let contents = std::fs::read_to_string(&file).unwrap();
-        let value: serde_json::Value = hcl::from_str(&contents).unwrap();
+    let mut ignored_lints = HashMap::new();
+    let mut block_locations = HashMap::new();
+    for file in files.files {
+        let comments = extract_ignored_lints(&file.contents);
+        let block_lines = extract_block_lines(&file.contents);
+        for line in &comments.detached {
+            warn!(
+                "{}:{}: the linter:ignore comment is not directly above a block and ignores nothing",
+                file.path.display(),
+                line
+            );
+        }
+        for address in comments.blocks.keys() {
+            if !address.starts_with("module.") {
+                let location = match block_lines.get(address) {
+                    Some(line) => format!("{}:{}", file.path.display(), line),
+                    None => file.path.display().to_string(),
+                };
+                warn!(
+                    "{}: linter:ignore comments are only supported above module blocks, the comment above {} ignores nothing",
+                    location, address
+                );
+            }
+        }
+        ignored_lints.extend(comments.blocks);
+        for (address, line) in block_lines {
+            block_locations.insert(address, (file.path.clone(), line));
+        }
+        let value: serde_json::Value = hcl::from_str(&file.contents).map_err(|e| {
+            PluginErrors::PluginError(format!("Failed to parse {}: {}", file.path.display(), e))
+        })?;
         json.push(value);
     }
+    let mut modules = extract_modules(&json);
+    for module in modules.iter_mut() {
+        if let Some(lints) = ignored_lints.get(&module.address()) {
+            module.ignored_lints = lints.clone();
+        }
+    }
     let mut hcl = HCL::default();
-    hcl.modules = extract_modules(&json);
+    hcl.modules = modules;
+    hcl.ignored_lints = ignored_lints;
+    hcl.block_locations = block_locations;
     hcl.terraform = extract_terraform(&json);
     hcl.lambda = extract_lambda(&json);
     hcl.api_config = extract_api_config(&json, hcl.lambda.clone());
-    hcl
+    Ok(hcl)
+}
+
+/// Finds the top level blocks and returns the line, starting at 1, of each block per address, e.g.
+/// `module.service_api`.
+pub fn extract_block_lines(contents: &str) -> HashMap<String, u32> {
+    let mut lines = HashMap::new();
+    for (index, line) in contents.lines().enumerate() {
+        if line.starts_with(char::is_whitespace) || line.starts_with('#') || line.starts_with("//")
+        {
+            continue;
+        }
+        if let Some((header, _)) = line.split_once('{') {
+            let address = block_address(header);
+            if !address.is_empty() {
+                lines.insert(address, index as u32 + 1);
+            }
+        }
+    }
+    lines
+}
+
+/// The address of a block from its header, e.g. `module.service_api` for `module "service_api"`.
+fn block_address(header: &str) -> String {
+    header
+        .split_whitespace()
+        .map(|label| label.trim_matches('"'))
+        .collect::<Vec<&str>>()
+        .join(".")
+}
+
+/// The `# linter:ignore "<lint name>"` comments of a file.
+#[derive(Debug, Default)]
+pub struct IgnoreComments {
+    /// The ignored lints per address of the block below the comments, e.g. `module.service_api`.
+    pub blocks: HashMap<String, Vec<String>>,
+    /// Lines, starting at 1, of the comments that are not directly above a block or have no lint
+    /// names, and ignore nothing.
+    pub detached: Vec<u32>,
+}
+
+/// Finds the `# linter:ignore "<lint name>"` comments and returns the ignored lints per address
+/// of the block below them. Several lints can be ignored with one comment or with consecutive
+/// comments.
+pub fn extract_ignored_lints(contents: &str) -> IgnoreComments {
+    let mut comments = IgnoreComments::default();
+    let mut pending: Vec<String> = Vec::new();
+    // Line of the first of the consecutive comments above the current line.
+    let mut pending_line = None;
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if let Some(comment) = line.strip_prefix('#').or_else(|| line.strip_prefix("//")) {
+            if let Some(directive) = comment.trim().strip_prefix("linter:ignore") {
+                pending_line.get_or_insert(index as u32 + 1);
+                pending.extend(
+                    directive
+                        .split('"')
+                        .skip(1)
+                        .step_by(2)
+                        .map(|name| name.to_string()),
+                );
+            }
+            continue;
+        }
+        if let Some(comment_line) = pending_line.take() {
+            match line.split_once('{') {
+                Some((header, _)) if !pending.is_empty() => comments
+                    .blocks
+                    .entry(block_address(header))
+                    .or_default()
+                    .append(&mut pending),
+                _ => comments.detached.push(comment_line),
+            }
+            pending.clear();
+        }
+    }
+    if let Some(comment_line) = pending_line {
+        comments.detached.push(comment_line);
+    }
+    comments
 }
 
-fn extract_modules(json: &Vec<serde_json::Value>) -> Vec<Module> {
+fn extract_modules(json: &[serde_json::Value]) -> Vec<Module> {
     let mut modules = Vec::new();
     for value in json {
-        value.get("module").map(|x| match x {
-            serde_json::Value::Object(s) => modules.extend(s.iter().map(|record| {
-                debug!("Record: {:#?}", record);
-                Module {
-                    name: record.0.to_string(),
-                    source: record
-                        .1
-                        .get("source")
-                        .expect("Source should be set")
-                        .to_string(),
-                    version: record
-                        .1
-                        .get("version")
-                        .unwrap_or_else(|| panic!("Version should be set: {}", record.0))
-                        .to_string(),
-                    variables: value
-                        .as_object()
-                        .unwrap()
-                        .iter()
-                        .map(|(k, v)| Variable {
-                            name: k.to_string(),
-                            value: v.to_string(),
-                        })
-                        .collect(),
-                }
-            })),
-            _ => todo!(),
-        });
+        if let Some(x) = value.get("module") {
+            match x {
+                serde_json::Value::Object(s) => modules.extend(s.iter().map(|record| {
+                    debug!("Record: {:#?}", record);
+                    Module {
+                        name: record.0.to_string(),
+                        source: record
+                            .1
+                            .get("source")
+                            .expect("Source should be set")
+                            .to_string(),
+                        version: record
+                            .1
+                            .get("version")
+                            .unwrap_or_else(|| panic!("Version should be set: {}", record.0))
+                            .to_string(),
+                        variables: value
+                            .as_object()
+                            .unwrap()
+                            .iter()
+                            .map(|(k, v)| Variable {
+                                name: k.to_string(),
+                                value: v.to_string(),
+                            })
+                            .collect(),
+                        ignored_lints: Vec::new(),
+                    }
+                })),
+                _ => todo!(),
+            }
+        }
     }
     modules
 }
 
-fn extract_terraform(json: &Vec<serde_json::Value>) -> Vec<Terraform> {
+fn extract_terraform(json: &[serde_json::Value]) -> Vec<Terraform> {
     let mut terraform = Vec::new();
     for value in json {
-        value.get("terraform").map(|x| match x {
-            serde_json::Value::Object(s) => {
-                let required_version = s.get("required_version").map(|x| x.to_string());
-                let backend: Option<Vec<Backend>> = s.get("backend").map(|x| match x {
-                    serde_json::Value::Object(s) => s
-                        .iter()
-                        .map(|(k, _v)| Backend {
-                            name: k.to_string(),
-                        })
-                        .collect(),
-                    _ => unreachable!(),
-                });
-                let required_providers: Option<Vec<RequiredProvider>> =
-                    s.get("required_providers").map(|x| match x {
+        if let Some(x) = value.get("terraform") {
+            match x {
+                serde_json::Value::Object(s) => {
+                    let required_version = s.get("required_version").map(|x| x.to_string());
+                    let backend: Option<Vec<Backend>> = s.get("backend").map(|x| match x {
                         serde_json::Value::Object(s) => s
                             .iter()
-                            .map(|(k, v)| RequiredProvider {
+                            .map(|(k, _v)| Backend {
                                 name: k.to_string(),
-                                source: v.get("source").unwrap().to_string(),
-                                version: v.get("version").unwrap().to_string(),
                             })
                             .collect(),
                         _ => unreachable!(),
                     });
+                    let required_providers: Option<Vec<RequiredProvider>> =
+                        s.get("required_providers").map(|x| match x {
+                            serde_json::Value::Object(s) => s
+                                .iter()
+                                .map(|(k, v)| RequiredProvider {
+                                    name: k.to_string(),
+                                    source: v.get("source").unwrap().to_string(),
+                                    version: v.get("version").unwrap().to_string(),
+                                })
+                                .collect(),
+                            _ => unreachable!(),
+                        });
 
-                let backend = if let Some(backend) = backend {
-                    if backend.is_empty() {
-                        None
-                    } else if backend.len() > 1 {
-                        panic!("backend can only have one value");
+                    let backend = if let Some(backend) = backend {
+                        if backend.is_empty() {
+                            None
+                        } else if backend.len() > 1 {
+                            panic!("backend can only have one value");
+                        } else {
+                            Some(backend[0].clone())
+                        }
                     } else {
-                        Some(backend[0].clone())
-                    }
-                } else {
-                    None
-                };
-                terraform.push(Terraform {
-                    required_version,
-                    backend,
-                    required_providers: required_providers.unwrap_or_default(),
-                })
+                        None
+                    };
+                    terraform.push(Terraform {
+                        required_version,
+                        backend,
+                        required_providers: required_providers.unwrap_or_default(),
+                    })
+                }
+                _ => todo!(),
             }
-            _ => todo!(),
-        });
+        }
     }
     terraform
 }
 
-fn extract_lambda(json: &Vec<serde_json::Value>) -> Vec<Lambda> {
+fn extract_lambda(json: &[serde_json::Value]) -> Vec<Lambda> {
     let mut lambdas = Vec::new();
     for value in json {
         let l = value
@@ -170,7 +289,7 @@ fn extract_lambda(json: &Vec<serde_json::Value>) -> Vec<Lambda> {
     lambdas
 }
 
-fn extract_api_config(json: &Vec<serde_json::Value>, lambdas: Vec<Lambda>) -> Option<ApiConfig> {
+fn extract_api_config(json: &[serde_json::Value], lambdas: Vec<Lambda>) -> Option<ApiConfig> {
     let s = json.iter().find_map(|x| {
         x.get("module")
             .and_then(|service| service.get("service_api"))
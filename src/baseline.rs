@@ -0,0 +1,114 @@
+use std::{collections::BTreeMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::report::LintOutcome;
+
+const BASELINE_VERSION: u32 = 1;
+
+/// Rows that failed each lint when the baseline was written. Rows in the baseline are not
+/// reported so that new lints can be adopted without fixing every existing violation first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub version: u32,
+    /// Rows per lint name.
+    #[serde(default)]
+    pub lints: BTreeMap<String, Vec<serde_json::Value>>,
+}
+
+impl Baseline {
+    /// Creates a baseline from the rows that failed the lints. Lints the plugins could not run
+    /// are left out.
+    pub fn from_outcomes(outcomes: &[LintOutcome]) -> Self {
+        let mut lints: BTreeMap<String, Vec<serde_json::Value>> = BTreeMap::new();
+        for outcome in outcomes {
+            if outcome.error.is_none() && !outcome.rows.is_empty() {
+                lints
+                    .entry(outcome.name.clone())
+                    .or_default()
+                    .extend(outcome.rows.iter().cloned());
+            }
+        }
+        Self {
+            version: BASELINE_VERSION,
+            lints,
+        }
+    }
+
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let baseline: Self = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        if baseline.version != BASELINE_VERSION {
+            return Err(anyhow::anyhow!(
+                "Baseline {} has version {} but version {} is supported",
+                path.display(),
+                baseline.version,
+                BASELINE_VERSION
+            ));
+        }
+        Ok(baseline)
+    }
+
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Removes the rows that are in the baseline from the outcomes and records the baseline rows
+    /// that no longer occur as fixed.
+    pub fn apply(&self, outcomes: &mut [LintOutcome]) {
+        for outcome in outcomes.iter_mut() {
+            if outcome.error.is_some() {
+                continue;
+            }
+            let Some(baseline_rows) = self.lints.get(&outcome.name) else {
+                continue;
+            };
+            outcome.fixed = baseline_rows
+                .iter()
+                .filter(|row| !outcome.rows.contains(row))
+                .cloned()
+                .collect();
+            let rows_count = outcome.rows.len();
+            outcome.rows.retain(|row| !baseline_rows.contains(row));
+            outcome.baselined = rows_count - outcome.rows.len();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plugin_core::LintResult;
+    use serde_json::json;
+
+    use super::*;
+    use crate::report::tests::outcome;
+
+    #[test]
+    fn test_apply_baseline() {
+        let delete_pets = json!({"path": "/pets/{id}", "method": "DELETE"});
+        let delete_users = json!({"path": "/users/{id}", "method": "DELETE"});
+        let delete_orders = json!({"path": "/orders/{id}", "method": "DELETE"});
+        let baseline = Baseline::from_outcomes(&[outcome(
+            LintResult::Error("No DELETE method allowed".to_string()),
+            vec![delete_pets.clone(), delete_orders.clone()],
+        )]);
+
+        let mut outcomes = vec![outcome(
+            LintResult::Error("No DELETE method allowed".to_string()),
+            vec![delete_pets, delete_users.clone()],
+        )];
+        baseline.apply(&mut outcomes);
+        assert_eq!(outcomes[0].rows, vec![delete_users]);
+        assert_eq!(outcomes[0].baselined, 1);
+        assert_eq!(outcomes[0].fixed, vec![delete_orders]);
+    }
+
+    #[test]
+    fn test_baseline_leaves_out_passing_lints() {
+        let baseline = Baseline::from_outcomes(&[outcome(
+            LintResult::Error("No DELETE method allowed".to_string()),
+            Vec::new(),
+        )]);
+        assert!(baseline.lints.is_empty());
+    }
+}
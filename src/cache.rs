@@ -0,0 +1,176 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use simplelog::debug;
+
+/// How long the cached copy of a url that is not pinned with a sha256 digest is used before the url
+/// is fetched again.
+const UNPINNED_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// On-disk cache of the plugins and lints files loaded from urls, keyed by the url.
+pub struct RemoteCache {
+    dir: PathBuf,
+    /// Only use the cached copies instead of fetching the urls.
+    offline: bool,
+    /// How long the cached copies of unpinned urls are used.
+    ttl: Duration,
+}
+
+impl RemoteCache {
+    /// Uses `LINTER_CACHE_DIR` as the cache directory, falling back to the `linter` directory in
+    /// the user's cache directory.
+    pub fn new(offline: bool) -> Self {
+        let env_dir = |name: &str| std::env::var_os(name).map(PathBuf::from);
+        let dir = env_dir("LINTER_CACHE_DIR")
+            .or_else(|| env_dir("XDG_CACHE_HOME").map(|dir| dir.join("linter")))
+            .or_else(|| env_dir("LOCALAPPDATA").map(|dir| dir.join("linter")))
+            .or_else(|| env_dir("HOME").map(|dir| dir.join(".cache").join("linter")))
+            .unwrap_or_else(|| std::env::temp_dir().join("linter"));
+        Self::with_dir(dir, offline)
+    }
+
+    pub fn with_dir(dir: PathBuf, offline: bool) -> Self {
+        Self {
+            dir,
+            offline,
+            ttl: UNPINNED_TTL,
+        }
+    }
+
+    fn path(&self, url: &str) -> PathBuf {
+        self.dir.join(sha256::digest(url))
+    }
+
+    /// Gets the contents of the url. A cached copy is used when the contents are pinned with a
+    /// sha256 digest that the copy matches, when the contents are not pinned and the copy is less
+    /// than an hour old, or when offline. Otherwise the url is fetched and the copy is updated.
+    pub fn fetch(&self, url: &str, sha256: Option<&str>) -> anyhow::Result<Vec<u8>> {
+        let path = self.path(url);
+        if let Ok(contents) = std::fs::read(&path) {
+            match sha256 {
+                Some(sha256) if self.offline => {
+                    verify_sha256(&contents, sha256, url)?;
+                    return Ok(contents);
+                }
+                Some(sha256) if verify_sha256(&contents, sha256, url).is_ok() => {
+                    debug!("Using the cached copy of {}", url);
+                    return Ok(contents);
+                }
+                None if self.offline => return Ok(contents),
+                None if self.is_fresh(&path) => {
+                    debug!("Using the cached copy of {}", url);
+                    return Ok(contents);
+                }
+                _ => {}
+            }
+        } else if self.offline {
+            return Err(anyhow::anyhow!(
+                "{} is not cached and cannot be fetched when offline",
+                url
+            ));
+        }
+        debug!("Fetching {}", url);
+        let contents = reqwest::blocking::get(url)?
+            .error_for_status()?
+            .bytes()?
+            .to_vec();
+        if let Some(sha256) = sha256 {
+            verify_sha256(&contents, sha256, url)?;
+        }
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(&path, &contents)?;
+        Ok(contents)
+    }
+
+    /// Whether the cached copy was written within the ttl.
+    fn is_fresh(&self, path: &Path) -> bool {
+        std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| modified.elapsed().is_ok_and(|age| age < self.ttl))
+    }
+}
+
+/// Checks that the sha256 digest of the contents is the expected hex encoded digest.
+pub fn verify_sha256(contents: &[u8], expected: &str, source: &str) -> anyhow::Result<()> {
+    let digest = sha256::digest(contents);
+    if !digest.eq_ignore_ascii_case(expected.trim()) {
+        return Err(anyhow::anyhow!(
+            "The sha256 digest of {} is {} but {} is expected",
+            source,
+            digest,
+            expected
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cache in a new temporary directory, which is removed when the returned `TempDir` is dropped.
+    fn cache(offline: bool) -> (tempfile::TempDir, RemoteCache) {
+        let dir = tempfile::TempDir::new().unwrap();
+        let cache = RemoteCache::with_dir(dir.path().to_path_buf(), offline);
+        (dir, cache)
+    }
+
+    #[test]
+    fn test_offline_uses_cached_copy() {
+        let (_dir, cache) = cache(true);
+        let url = "https://example.com/lints.json";
+        std::fs::write(cache.path(url), r#"{"lints": []}"#).unwrap();
+        let contents = cache.fetch(url, None).unwrap();
+        assert_eq!(contents, br#"{"lints": []}"#);
+        let digest = sha256::digest(&contents);
+        assert!(cache.fetch(url, Some(&digest)).is_ok());
+    }
+
+    #[test]
+    fn test_offline_without_cached_copy() {
+        let (_dir, cache) = cache(true);
+        assert!(cache.fetch("https://example.com/lints.json", None).is_err());
+    }
+
+    #[test]
+    fn test_offline_hash_mismatch() {
+        let (_dir, cache) = cache(true);
+        let url = "https://example.com/plugin.wasm";
+        std::fs::write(cache.path(url), "not the plugin").unwrap();
+        let err = cache
+            .fetch(url, Some(&sha256::digest("the plugin")))
+            .unwrap_err();
+        assert!(err.to_string().contains("sha256 digest"));
+    }
+
+    #[test]
+    fn test_online_uses_fresh_unpinned_copy() {
+        let (_dir, cache) = cache(false);
+        let url = "https://example.invalid/lints.json";
+        std::fs::write(cache.path(url), r#"{"lints": []}"#).unwrap();
+        assert_eq!(cache.fetch(url, None).unwrap(), br#"{"lints": []}"#);
+    }
+
+    #[test]
+    fn test_online_fetches_stale_unpinned_copy() {
+        let (_dir, mut cache) = cache(false);
+        cache.ttl = Duration::ZERO;
+        let url = "https://example.invalid/lints.json";
+        std::fs::write(cache.path(url), r#"{"lints": []}"#).unwrap();
+        let err = cache.fetch(url, None).unwrap_err();
+        // The error comes from fetching the url, which cannot be resolved.
+        let err = err
+            .downcast_ref::<reqwest::Error>()
+            .unwrap_or_else(|| panic!("expected a fetch error: {}", err));
+        assert_eq!(err.url().map(|url| url.as_str()), Some(url));
+    }
+
+    #[test]
+    fn test_verify_sha256() {
+        let digest = sha256::digest("contents");
+        assert!(verify_sha256(b"contents", &digest.to_uppercase(), "file").is_ok());
+        assert!(verify_sha256(b"other contents", &digest, "file").is_err());
+    }
+}
@@ -0,0 +1,202 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+};
+
+use crate::{
+    cache::RemoteCache,
+    plugin_config::{LintFilter, LinterConfig, LinterData},
+    wasm_main::{configured_lints, filter_selects, LintData},
+};
+
+/// Prints the documentation, query and arguments of the lints with the name, including the lints
+/// that are off or left out by `--only` and `--skip`.
+pub fn explain_main(config: LinterConfig, cache: &RemoteCache, name: &str) -> anyhow::Result<()> {
+    let linter_data = LinterData::from_linter_config(config)?;
+    let lints = configured_lints(&linter_data, &LintFilter::default(), cache)?;
+    let mut found = false;
+    for lint in lints.iter().filter(|lint| lint.name == name) {
+        let selected = filter_selects(&linter_data.filter, lint);
+        println!("{}", explanation(lint, selected)?);
+        found = true;
+    }
+    if !found {
+        return Err(anyhow::anyhow!("No lint is named {}", name));
+    }
+    Ok(())
+}
+
+/// The explanation of the lint, which tells when the lint is not run as it is left out by `--only`
+/// or `--skip`.
+fn explanation(lint: &LintData, selected: bool) -> anyhow::Result<String> {
+    let mut text = String::new();
+    writeln!(text, "# {}", lint.name)?;
+    writeln!(text)?;
+    writeln!(text, "Severity: {}", lint.severity())?;
+    if !selected {
+        writeln!(text, "Not run: left out by --only or --skip")?;
+    }
+    if let Some(plugin) = &lint.plugin {
+        match &lint.compared_plugin {
+            Some(compared_plugin) => {
+                writeln!(text, "Plugin: {} compared with {}", plugin, compared_plugin)?
+            }
+            None => writeln!(text, "Plugin: {}", plugin)?,
+        }
+    }
+    if let Some(category) = &lint.category {
+        writeln!(text, "Lints file: {}", category)?;
+    }
+    if !lint.tags.is_empty() {
+        writeln!(text, "Tags: {}", lint.tags.join(", "))?;
+    }
+    writeln!(text, "Message: {}", lint.message())?;
+
+    let docs = &lint.docs;
+    let sections = [
+        ("Description", &docs.description),
+        ("Rationale", &docs.rationale),
+        ("Remediation", &docs.remediation),
+    ];
+    for (title, section) in sections {
+        if let Some(section) = section {
+            writeln!(text)?;
+            writeln!(text, "## {}", title)?;
+            writeln!(text)?;
+            writeln!(text, "{}", section.trim_end())?;
+        }
+    }
+    for (title, examples) in [
+        ("Good examples", &docs.good_examples),
+        ("Bad examples", &docs.bad_examples),
+    ] {
+        if !examples.is_empty() {
+            writeln!(text)?;
+            writeln!(text, "## {}", title)?;
+            for example in examples {
+                writeln!(text)?;
+                writeln!(text, "```")?;
+                writeln!(text, "{}", example.trim_end())?;
+                writeln!(text, "```")?;
+            }
+        }
+    }
+    if let Some(docs_url) = &docs.docs_url {
+        writeln!(text)?;
+        writeln!(text, "See {}", docs_url)?;
+    }
+
+    write_query(&mut text, "Query", &lint.lint, &lint.args)?;
+    if let Some(compared_lint) = &lint.compared_lint {
+        write_query(
+            &mut text,
+            "Compared query",
+            compared_lint,
+            &lint.compared_args.clone().unwrap_or_default(),
+        )?;
+    }
+    Ok(text.trim_end().to_string())
+}
+
+fn write_query(
+    text: &mut String,
+    title: &str,
+    query: &str,
+    args: &HashMap<String, serde_json::Value>,
+) -> anyhow::Result<()> {
+    writeln!(text)?;
+    writeln!(text, "## {}", title)?;
+    writeln!(text)?;
+    writeln!(text, "```graphql")?;
+    writeln!(text, "{}", query.trim_end())?;
+    writeln!(text, "```")?;
+    if !args.is_empty() {
+        // Sorted so that the arguments are always printed in the same order.
+        let args: BTreeMap<&String, &serde_json::Value> = args.iter().collect();
+        writeln!(text)?;
+        writeln!(text, "Arguments:")?;
+        writeln!(text)?;
+        writeln!(text, "```yaml")?;
+        write!(text, "{}", serde_yaml::to_string(&args)?)?;
+        writeln!(text, "```")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{plugin_config::Severity, wasm_main::LintDocs};
+
+    #[test]
+    fn test_explanation() {
+        let lint = LintData {
+            name: "No DELETE method".to_string(),
+            lint: "{ Paths { path @output } }\n".to_string(),
+            args: HashMap::from([("method".to_string(), json!("delete"))]),
+            warning: Some("No DELETE method allowed".to_string()),
+            plugin: Some("openapi".to_string()),
+            tags: vec!["style".to_string()],
+            category: Some("Methods".to_string()),
+            docs: LintDocs {
+                rationale: Some("Resources are archived instead".to_string()),
+                bad_examples: vec!["delete:\n  summary: Delete a pet\n".to_string()],
+                docs_url: Some("https://example.com/lints/no-delete".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            explanation(&lint, true).unwrap(),
+            r#"# No DELETE method
+
+Severity: warning
+Plugin: openapi
+Lints file: Methods
+Tags: style
+Message: No DELETE method allowed
+
+## Rationale
+
+Resources are archived instead
+
+## Bad examples
+
+```
+delete:
+  summary: Delete a pet
+```
+
+See https://example.com/lints/no-delete
+
+## Query
+
+```graphql
+{ Paths { path @output } }
+```
+
+Arguments:
+
+```yaml
+method: delete
+```"#
+        );
+    }
+
+    #[test]
+    fn test_explanation_of_lint_not_run() {
+        let mut lint = LintData {
+            name: "No DELETE method".to_string(),
+            lint: "{ Paths { path @output } }\n".to_string(),
+            warning: Some("No DELETE method allowed".to_string()),
+            ..Default::default()
+        };
+        lint.set_severity(Severity::Off);
+        let explanation = explanation(&lint, false).unwrap();
+        assert!(explanation.starts_with(
+            "# No DELETE method\n\nSeverity: off\nNot run: left out by --only or --skip\n"
+        ));
+    }
+}
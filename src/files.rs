@@ -0,0 +1,205 @@
+use std::path::Path;
+
+use ignore::{gitignore::Gitignore, Match, WalkBuilder};
+use plugin_core::{AvailableFiles, Files, FilteredFile};
+use simplelog::debug;
+
+/// Finds the files in the directory that the plugin requested, skipping the ignored directories,
+/// `.git` and the files ignored by `.gitignore` files. A directory that is a single file is always given to
+/// the plugin.
+pub fn collect_files(directory: &Path, requested: &Files) -> anyhow::Result<AvailableFiles> {
+    if !directory.exists() {
+        return Err(anyhow::anyhow!(
+            "{} is not a file or directory",
+            directory.display()
+        ));
+    }
+    let ignored_dirs = requested.ignored_dirs.clone();
+    let walker = WalkBuilder::new(directory)
+        .hidden(false)
+        .require_git(false)
+        .filter_entry(move |entry| {
+            !(entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_dir())
+                && (entry.file_name() == ".git"
+                    || ignored_dirs
+                        .iter()
+                        .any(|dir| entry.file_name() == dir.as_str())))
+        })
+        .build();
+    let mut files = Vec::new();
+    for entry in walker {
+        let entry = entry?;
+        if !entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+        {
+            continue;
+        }
+        let path = entry.path();
+        if entry.depth() > 0 && !has_extension(path, &requested.extensions) {
+            continue;
+        }
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                debug!("Skipping {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        let relative_path = match path.strip_prefix(directory) {
+            Ok(relative_path) if entry.depth() > 0 => relative_path,
+            _ => Path::new(entry.file_name()),
+        };
+        files.push(FilteredFile {
+            name: entry.file_name().to_string_lossy().to_string(),
+            path: relative_path.to_path_buf(),
+            contents,
+        });
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(AvailableFiles { files })
+}
+
+/// Whether a path in the directory is left out by [`collect_files`] because it is in `.git` or in
+/// one of the ignored directories, or is ignored by a `.gitignore` or `.ignore` file in the
+/// directory or one of its subdirectories.
+pub fn is_ignored(directory: &Path, path: &Path, requested: &Files) -> bool {
+    let Ok(relative_path) = path.strip_prefix(directory) else {
+        return false;
+    };
+    if relative_path.components().any(|component| {
+        let name = component.as_os_str();
+        name == ".git"
+            || requested
+                .ignored_dirs
+                .iter()
+                .any(|dir| name == dir.as_str())
+    }) {
+        return true;
+    }
+    // The deepest ignore file that matches the path decides, as in a walk of the directory.
+    let mut ignore_dirs = vec![directory.to_path_buf()];
+    if let Some(parent) = relative_path.parent() {
+        for component in parent.components() {
+            let dir = ignore_dirs[ignore_dirs.len() - 1].join(component);
+            ignore_dirs.push(dir);
+        }
+    }
+    let is_dir = path.is_dir();
+    for dir in ignore_dirs.iter().rev() {
+        for ignore_file in [".ignore", ".gitignore"] {
+            let (ignore, _) = Gitignore::new(dir.join(ignore_file));
+            match ignore.matched_path_or_any_parents(path, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+    }
+    false
+}
+
+fn has_extension(path: &Path, extensions: &[String]) -> bool {
+    extensions.is_empty()
+        || path
+            .extension()
+            .is_some_and(|extension| extensions.iter().any(|ext| extension == ext.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// Temporary directory with files to collect, which is removed when it is dropped.
+    fn directory() -> tempfile::TempDir {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        std::fs::create_dir_all(dir.join("modules").join("api")).unwrap();
+        std::fs::create_dir_all(dir.join(".terraform").join("modules")).unwrap();
+        std::fs::create_dir_all(dir.join("generated")).unwrap();
+        std::fs::create_dir_all(dir.join(".git")).unwrap();
+        std::fs::write(dir.join(".git").join("HEAD"), "ref: refs/heads/main").unwrap();
+        std::fs::write(dir.join("main.tf"), "module \"api\" {}").unwrap();
+        std::fs::write(dir.join("README.md"), "# Readme").unwrap();
+        std::fs::write(dir.join("modules").join("api").join("main.tf"), "").unwrap();
+        std::fs::write(dir.join(".terraform").join("modules").join("main.tf"), "").unwrap();
+        std::fs::write(dir.join("generated").join("main.tf"), "").unwrap();
+        std::fs::write(dir.join(".gitignore"), "generated/\n").unwrap();
+        temp_dir
+    }
+
+    fn paths(files: &AvailableFiles) -> Vec<PathBuf> {
+        files.files.iter().map(|file| file.path.clone()).collect()
+    }
+
+    #[test]
+    fn test_collect_requested_files() {
+        let temp_dir = directory();
+        let dir = temp_dir.path();
+        let requested = Files {
+            extensions: vec!["tf".to_string()],
+            ignored_dirs: vec![".terraform".to_string()],
+        };
+        let files = collect_files(dir, &requested).unwrap();
+        assert_eq!(
+            paths(&files),
+            vec![
+                PathBuf::from("main.tf"),
+                PathBuf::from("modules").join("api").join("main.tf")
+            ]
+        );
+        assert_eq!(files.files[0].name, "main.tf");
+        assert_eq!(files.files[0].contents, "module \"api\" {}");
+    }
+
+    #[test]
+    fn test_collect_all_files() {
+        let temp_dir = directory();
+        let dir = temp_dir.path();
+        let files = collect_files(dir, &Files::default()).unwrap();
+        assert_eq!(files.files.len(), 5);
+        assert!(paths(&files).contains(&PathBuf::from("README.md")));
+    }
+
+    #[test]
+    fn test_is_ignored() {
+        let temp_dir = directory();
+        let dir = temp_dir.path();
+        let requested = Files {
+            ignored_dirs: vec![".terraform".to_string()],
+            ..Default::default()
+        };
+        let ignored = |path: PathBuf| is_ignored(dir, &dir.join(path), &requested);
+        assert!(ignored(PathBuf::from(".git").join("index")));
+        assert!(ignored(
+            PathBuf::from(".terraform").join("modules").join("main.tf")
+        ));
+        assert!(ignored(PathBuf::from("generated").join("main.tf")));
+        assert!(!ignored(
+            PathBuf::from("modules").join("api").join("main.tf")
+        ));
+        assert!(!ignored(PathBuf::from("main.tf")));
+        assert!(!is_ignored(
+            dir,
+            Path::new("/elsewhere/main.tf"),
+            &requested
+        ));
+    }
+
+    #[test]
+    fn test_collect_single_file() {
+        let temp_dir = directory();
+        let dir = temp_dir.path();
+        let requested = Files {
+            extensions: vec!["yaml".to_string()],
+            ..Default::default()
+        };
+        let files = collect_files(&dir.join("main.tf"), &requested).unwrap();
+        assert_eq!(paths(&files), vec![PathBuf::from("main.tf")]);
+        assert!(collect_files(&dir.join("missing.tf"), &requested).is_err());
+    }
+}
@@ -0,0 +1,288 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
+
+use extism::Plugin;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Response};
+use lsp_types::{
+    notification::{
+        DidOpenTextDocument, DidSaveTextDocument, Notification as _, PublishDiagnostics,
+    },
+    Diagnostic, DiagnosticSeverity, DidOpenTextDocumentParams, DidSaveTextDocumentParams,
+    NumberOrString, Position, PublishDiagnosticsParams, Range, ServerCapabilities,
+    TextDocumentSyncCapability, TextDocumentSyncOptions, TextDocumentSyncSaveOptions, Url,
+};
+use plugin_core::SourceLocation;
+
+use crate::{
+    cache::RemoteCache,
+    plugin_config::{LinterConfig, LinterData, PluginData, Severity},
+    report::{row_location, LintOutcome},
+    wasm_main::{
+        lint_outcomes, linter_lints, load_plugin_into_sets, plugin_locations, plugin_metadata,
+    },
+};
+
+/// Runs a language server over stdio that lints when a file linted by one of the plugins is opened
+/// or saved, and publishes the rows that fail the lints as diagnostics at the file and line they
+/// came from.
+pub fn lsp_main(config: LinterConfig, cache: &RemoteCache) -> anyhow::Result<()> {
+    let linter_data = LinterData::from_linter_config(config)?;
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = serde_json::to_value(ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(
+            TextDocumentSyncOptions {
+                open_close: Some(true),
+                save: Some(TextDocumentSyncSaveOptions::Supported(true)),
+                ..Default::default()
+            },
+        )),
+        ..Default::default()
+    })?;
+    connection.initialize(capabilities)?;
+
+    let mut server = LintServer::new(&linter_data, cache);
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    break;
+                }
+                let response = Response::new_err(
+                    request.id,
+                    ErrorCode::MethodNotFound as i32,
+                    format!("{} is not supported", request.method),
+                );
+                connection.sender.send(Message::Response(response))?;
+            }
+            Message::Notification(notification) => {
+                let uri = match notification.method.as_str() {
+                    DidOpenTextDocument::METHOD => {
+                        serde_json::from_value::<DidOpenTextDocumentParams>(notification.params)?
+                            .text_document
+                            .uri
+                    }
+                    DidSaveTextDocument::METHOD => {
+                        serde_json::from_value::<DidSaveTextDocumentParams>(notification.params)?
+                            .text_document
+                            .uri
+                    }
+                    _ => continue,
+                };
+                let Ok(path) = uri.to_file_path() else {
+                    continue;
+                };
+                for params in server.lint(&path) {
+                    let notification = Notification::new(PublishDiagnostics::METHOD.into(), params);
+                    connection
+                        .sender
+                        .send(Message::Notification(notification))?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+    // The writer thread stops once the connection is dropped.
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+struct LintServer<'a> {
+    linter_data: &'a LinterData,
+    cache: &'a RemoteCache,
+    plugin_sets: Vec<HashMap<String, Plugin>>,
+    /// Extensions of the files each loaded plugin lints.
+    file_types: HashMap<String, Vec<String>>,
+    /// Files that have diagnostics published, which are cleared when they no longer fail any lint.
+    published: BTreeSet<Url>,
+}
+
+impl<'a> LintServer<'a> {
+    fn new(linter_data: &'a LinterData, cache: &'a RemoteCache) -> Self {
+        Self {
+            linter_data,
+            cache,
+            plugin_sets: vec![HashMap::new()],
+            file_types: HashMap::new(),
+            published: BTreeSet::new(),
+        }
+    }
+
+    /// Loads the plugins that lint the changed file again, so that they read its new contents, and
+    /// the plugins that are not loaded yet. Returns whether any of the plugins lints the file.
+    fn load_plugins(&mut self, changed: &Path) -> anyhow::Result<bool> {
+        let mut lints_file = false;
+        for plugin_data in &self.linter_data.plugins {
+            let loaded = self.plugin_sets[0].contains_key(&plugin_data.name);
+            let lints_changed = loaded && self.lints_file(plugin_data, changed);
+            lints_file |= lints_changed;
+            if !loaded || lints_changed {
+                load_plugin_into_sets(plugin_data, self.cache, &mut self.plugin_sets)?;
+                let plugin = self.plugin_sets[0].get_mut(&plugin_data.name).unwrap();
+                let metadata = plugin_metadata(plugin_data, plugin)?;
+                self.file_types
+                    .insert(plugin_data.name.clone(), metadata.file_types);
+                lints_file |= !loaded && self.lints_file(plugin_data, changed);
+            }
+        }
+        Ok(lints_file)
+    }
+
+    /// Whether the file is in the plugin's directory and is one of the file types it lints.
+    fn lints_file(&self, plugin_data: &PluginData, path: &Path) -> bool {
+        let directory = canonical(&plugin_data.directory);
+        let file_types = self
+            .file_types
+            .get(&plugin_data.name)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        canonical(path).starts_with(&directory)
+            && path.extension().is_some_and(|extension| {
+                file_types
+                    .iter()
+                    .any(|file_type| extension == file_type.as_str())
+            })
+    }
+
+    /// Lints after the file changed and returns the diagnostics to publish.
+    fn lint(&mut self, changed: &Path) -> Vec<PublishDiagnosticsParams> {
+        match self.load_plugins(changed) {
+            Ok(true) => {}
+            Ok(false) => return Vec::new(),
+            Err(e) => {
+                eprintln!("Failed to load the plugins: {}", e);
+                return Vec::new();
+            }
+        }
+        let outcomes = match linter_lints(self.linter_data, self.cache)
+            .and_then(|lints| lint_outcomes(&lints, &mut self.plugin_sets))
+        {
+            Ok(outcomes) => outcomes,
+            Err(e) => {
+                eprintln!("Failed to run the lints: {}", e);
+                return Vec::new();
+            }
+        };
+        for outcome in &outcomes {
+            if let Some(err) = &outcome.error {
+                eprintln!("Error in the plugin running lint {}: {}", outcome.name, err);
+            }
+        }
+        let locations = plugin_locations(&mut self.plugin_sets[0]);
+        let directories = self
+            .linter_data
+            .plugins
+            .iter()
+            .map(|plugin_data| (plugin_data.name.clone(), canonical(&plugin_data.directory)))
+            .collect();
+        let mut params = Vec::new();
+        let mut published = BTreeSet::new();
+        for (path, diagnostics) in diagnostics(&outcomes, &locations, &directories) {
+            let Ok(uri) = Url::from_file_path(&path) else {
+                continue;
+            };
+            published.insert(uri.clone());
+            params.push(PublishDiagnosticsParams::new(uri, diagnostics, None));
+        }
+        for uri in self.published.difference(&published) {
+            params.push(PublishDiagnosticsParams::new(uri.clone(), Vec::new(), None));
+        }
+        self.published = published;
+        params
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Groups the failed rows of the lints per file, at the line of the block they came from. The
+/// rows of a compared lint can come from either plugin. Rows without a location are skipped.
+fn diagnostics(
+    outcomes: &[LintOutcome],
+    locations: &HashMap<String, Vec<SourceLocation>>,
+    directories: &HashMap<String, PathBuf>,
+) -> BTreeMap<PathBuf, Vec<Diagnostic>> {
+    let mut diagnostics: BTreeMap<PathBuf, Vec<Diagnostic>> = BTreeMap::new();
+    for outcome in outcomes {
+        for row in &outcome.rows {
+            let Some((path, line)) = row_location(outcome, row, locations, directories) else {
+                continue;
+            };
+            let line = line.saturating_sub(1);
+            diagnostics.entry(path).or_default().push(Diagnostic {
+                range: Range::new(Position::new(line, 0), Position::new(line + 1, 0)),
+                severity: Some(match outcome.severity() {
+                    Severity::Error => DiagnosticSeverity::ERROR,
+                    Severity::Warning => DiagnosticSeverity::WARNING,
+                    Severity::Info | Severity::Off => DiagnosticSeverity::INFORMATION,
+                }),
+                code: Some(NumberOrString::String(outcome.name.clone())),
+                source: Some("linter".to_string()),
+                message: outcome.row_message(row),
+                ..Default::default()
+            });
+        }
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use plugin_core::LintResult;
+    use serde_json::json;
+
+    use super::*;
+    use crate::report::tests::outcome;
+
+    #[test]
+    fn test_diagnostics() {
+        let location = |fields: serde_json::Value, line| SourceLocation {
+            fields: fields.as_object().unwrap().clone(),
+            path: PathBuf::from("api").join("pets.yaml"),
+            line,
+        };
+        let locations = HashMap::from([(
+            "openapi".to_string(),
+            vec![
+                location(json!({"path": "/pets"}), 6),
+                location(json!({"path": "/pets", "method": "DELETE"}), 12),
+            ],
+        )]);
+        let directories = HashMap::from([("openapi".to_string(), PathBuf::from("/repo"))]);
+        let outcomes = vec![
+            outcome(
+                LintResult::Error("No DELETE method allowed".to_string()),
+                vec![
+                    json!({"path": "/pets", "method": "DELETE"}),
+                    json!({"path": "/owners", "method": "DELETE"}),
+                ],
+            ),
+            outcome(
+                LintResult::Warning("Paths should have tags".to_string()),
+                vec![json!({"path": "/pets"})],
+            ),
+        ];
+        let diagnostics = diagnostics(&outcomes, &locations, &directories);
+        let file_diagnostics = &diagnostics[&PathBuf::from("/repo/api/pets.yaml")];
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(file_diagnostics.len(), 2);
+        assert_eq!(file_diagnostics[0].range.start, Position::new(11, 0));
+        assert_eq!(
+            file_diagnostics[0].severity,
+            Some(DiagnosticSeverity::ERROR)
+        );
+        assert_eq!(file_diagnostics[0].message, "No DELETE method allowed");
+        assert_eq!(
+            file_diagnostics[0].code,
+            Some(NumberOrString::String("No DELETE method".to_string()))
+        );
+        assert_eq!(file_diagnostics[1].range.start, Position::new(5, 0));
+        assert_eq!(
+            file_diagnostics[1].severity,
+            Some(DiagnosticSeverity::WARNING)
+        );
+    }
+}
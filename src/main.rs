@@ -1,18 +1,38 @@
 use std::path::PathBuf;
 
 use anyhow::Ok;
-use clap::{Args, Parser};
+use clap::{error::ErrorKind, Args, CommandFactory, Parser};
 
+mod baseline;
+mod cache;
 mod compare_main;
+mod explain_main;
+mod files;
+mod lsp_main;
+mod output_main;
 mod plugin_config;
+mod report;
+mod schema_main;
+mod test_main;
+mod validate;
 mod wasm_main;
+mod watch_main;
 
+use cache::RemoteCache;
 use compare_main::compare_lints_main;
+use explain_main::explain_main;
+use lsp_main::lsp_main;
+use output_main::{output_main, OutputFormat};
+use plugin_config::{discover_compare_config_file, LinterConfig, Severity};
+use report::ReportFormat;
+use schema_main::{schema_main, SchemaFormat};
 
 use serde::{Deserialize, Serialize};
 use simplelog::{Color, ColorChoice, ConfigBuilder, Level, LevelFilter, TermLogger, TerminalMode};
 
-use wasm_main::wasm_main;
+use test_main::test_main;
+use wasm_main::{wasm_main, LintOptions};
+use watch_main::watch_main;
 
 #[derive(Debug, Parser, Serialize, Deserialize)]
 pub struct CliArgs {
@@ -33,11 +53,35 @@ pub struct CliArgs {
 
 #[derive(Debug, Parser)]
 enum LinterCommands {
-    Lint(CLI),
-    Compare(CLI),
-    // TODO
-    // Test,
-    // Output,
+    Lint(LintCli),
+    /// Compare the results of the lints from two plugins
+    Compare(CompareCli),
+    /// Test the lints against their fixtures
+    Test(CLI),
+    /// Run a query against a plugin and print the resulting rows
+    Output(OutputCli),
+    /// Print the GraphQL schema of a plugin
+    Schema(SchemaCli),
+    /// Print the documentation, query and arguments of a lint
+    Explain(ExplainCli),
+    /// Publish lint results as diagnostics to editors over the Language Server Protocol on stdio
+    Lsp(CLI),
+}
+
+impl LinterCommands {
+    /// Where the logs are written. Commands that write a report, rows or a schema to stdout log to
+    /// stderr, so that stdout only has their output.
+    fn terminal_mode(&self) -> TerminalMode {
+        match self {
+            LinterCommands::Lint(lint_cli) if lint_cli.format != ReportFormat::Text => {
+                TerminalMode::Stderr
+            }
+            LinterCommands::Output(_) | LinterCommands::Schema(_) | LinterCommands::Lsp(_) => {
+                TerminalMode::Stderr
+            }
+            _ => TerminalMode::Stdout,
+        }
+    }
 }
 
 #[derive(Debug, Default, Args, Serialize, Deserialize)]
@@ -46,36 +90,232 @@ pub struct CLI {
     /// Verbose mode
     #[clap(short, long)]
     pub verbose: bool,
-    /// Config file to use. Defaults to linter_config.yaml
+    /// Config file to use. Defaults to the linter.yaml or linter_config.yaml file in the directory
+    /// or its closest parent that has one
     #[clap(short, long)]
     pub config: Option<PathBuf>,
     /// Directory containing files that need to be linted. Defaults to the current directory.
     #[clap(short, long)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dir: Option<PathBuf>,
+    /// Only use the cached copies of plugins and lints files loaded from urls
+    #[clap(long)]
+    pub offline: bool,
+    /// Path or url to the plugin, overriding the plugin in the config
+    #[clap(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<String>,
+    /// Lints file to use instead of the lints files in the config. Can be used multiple times.
+    #[clap(long = "lints-path")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lints_paths: Vec<PathBuf>,
+    /// Severity of a lint in the form of name=severity, where the severity is off, info, warning or
+    /// error. Can be used multiple times.
+    #[clap(long = "severity", value_parser = parse_severity)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub severities: Vec<(String, Severity)>,
+    /// Only run the lints with this name or tag, or in the lints file with this name. Can be used
+    /// multiple times.
+    #[clap(long)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub only: Vec<String>,
+    /// Do not run the lints with this name or tag, or in the lints file with this name. Can be used
+    /// multiple times.
+    #[clap(long)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skip: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct LintCli {
+    #[clap(flatten)]
+    pub cli: CLI,
+    /// Format of the lint results
+    #[clap(short, long, value_enum, default_value_t)]
+    pub format: ReportFormat,
+    /// File to write the report to. Defaults to stdout. Not supported by the text format
+    #[clap(short, long)]
+    pub output: Option<PathBuf>,
+    /// Number of instances of each plugin to run the lints with in parallel
+    #[clap(short, long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub jobs: u32,
+    /// Baseline file with rows that are not reported
+    #[clap(short, long)]
+    pub baseline: Option<PathBuf>,
+    /// Write the rows that fail the lints to a baseline file
+    #[clap(long, conflicts_with = "baseline")]
+    pub write_baseline: Option<PathBuf>,
+    /// Keep running and lint again whenever the linted files or the lints files change
+    #[clap(short, long, conflicts_with = "write_baseline")]
+    pub watch: bool,
+    /// Lowest severity of the lints that fail the linter when they fail: info, warning or error
+    #[clap(long, default_value = "error", value_parser = parse_fail_on)]
+    pub fail_on: Severity,
+    /// Fail when more than this number of rows fail warning lints
+    #[clap(long)]
+    pub max_warnings: Option<usize>,
+}
+
+impl LintCli {
+    /// Rejects an output file for the text format, which logs the results instead of writing a
+    /// report.
+    fn validate(&self) -> Result<(), clap::Error> {
+        if self.output.is_some() && self.format == ReportFormat::Text {
+            return Err(LinterCommands::command().error(
+                ErrorKind::ArgumentConflict,
+                "--output cannot be used with --format text, use --format sarif, junit or json to write a report",
+            ));
+        }
+        std::result::Result::Ok(())
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct CompareCli {
+    /// Verbose mode
+    #[clap(short, long)]
+    pub verbose: bool,
+    /// Compare config file with the main and secondary plugins. Defaults to the
+    /// linter_config.yaml file in the current directory or its closest parent that has one
+    #[clap(short, long)]
+    pub config: Option<PathBuf>,
+    /// Only use the cached copies of plugins and lints files loaded from urls
+    #[clap(long)]
+    pub offline: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ExplainCli {
+    #[clap(flatten)]
+    pub cli: CLI,
+    /// Name of the lint
+    pub lint: String,
+}
+
+#[derive(Debug, Args)]
+pub struct OutputCli {
+    /// Verbose mode
+    #[clap(short, long)]
+    pub verbose: bool,
+    /// Config file to use. Defaults to the linter.yaml or linter_config.yaml file in the current
+    /// directory or its closest parent that has one
+    #[clap(short, long)]
+    pub config: Option<PathBuf>,
+    /// Name of the plugin in the config file, or the path or url to a plugin. Defaults to the
+    /// plugin in the config file if there is only one.
+    #[clap(short, long)]
+    pub plugin: Option<String>,
+    /// Directory containing the files to be queried. Defaults to the plugin's directory.
+    #[clap(short, long)]
+    pub dir: Option<PathBuf>,
+    /// Only use the cached copy of a plugin loaded from a url
+    #[clap(long)]
+    pub offline: bool,
+    /// Query argument in the form of key=value. Can be used multiple times.
+    #[clap(short, long = "arg", value_parser = parse_query_arg)]
+    pub args: Vec<(String, serde_json::Value)>,
+    /// Output format of the rows
+    #[clap(short, long, value_enum, default_value_t)]
+    pub format: OutputFormat,
+    /// File containing the query
+    pub query: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct SchemaCli {
+    /// Verbose mode
+    #[clap(short, long)]
+    pub verbose: bool,
+    /// Path or url to the plugin
+    #[clap(short, long)]
+    pub plugin: String,
+    /// Only use the cached copy of a plugin loaded from a url
+    #[clap(long)]
+    pub offline: bool,
+    /// Print the schema as GraphQL or as a markdown reference page
+    #[clap(short, long, value_enum, default_value_t)]
+    pub format: SchemaFormat,
+}
+
+/// Parses a `key=value` query argument. Values that are valid JSON, such as numbers, are parsed
+/// as JSON and anything else is used as a string.
+fn parse_query_arg(arg: &str) -> Result<(String, serde_json::Value), String> {
+    let (key, value) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("invalid argument, expected key=value: {}", arg))?;
+    let value = serde_json::from_str(value)
+        .unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+    std::result::Result::Ok((key.to_string(), value))
+}
+
+/// Parses a `name=severity` severity override.
+fn parse_severity(arg: &str) -> Result<(String, Severity), String> {
+    let (name, severity) = arg
+        .rsplit_once('=')
+        .ok_or_else(|| format!("invalid severity, expected name=severity: {}", arg))?;
+    std::result::Result::Ok((name.to_string(), severity.parse()?))
+}
+
+/// Parses the severity of `--fail-on`, which cannot be off as lints that are off are not run.
+fn parse_fail_on(arg: &str) -> Result<Severity, String> {
+    match arg.parse()? {
+        Severity::Off => {
+            Err("lints that are off are not run, expected info, warning or error".to_string())
+        }
+        severity => std::result::Result::Ok(severity),
+    }
 }
 
 struct FinalCli {
     verbose: bool,
-    config: PathBuf,
+    /// Config file given with `--config`. The config file is discovered from `dir` if not given.
+    config: Option<PathBuf>,
     dir: PathBuf,
+    offline: bool,
+    /// Config set with the CLI flags, which overrides the config file and environment variables.
+    overrides: LinterConfig,
 }
 
 impl FinalCli {
     fn new(cli: CLI) -> anyhow::Result<Self> {
-        let config = cli
-            .config
-            .unwrap_or_else(|| PathBuf::from("linter_config.yaml"));
-        let dir = cli.dir.unwrap_or_else(|| std::env::current_dir().unwrap());
+        let dir = cli
+            .dir
+            .clone()
+            .unwrap_or_else(|| std::env::current_dir().unwrap());
+        let mut overrides = LinterConfig {
+            severity_overrides: cli.severities.into_iter().collect(),
+            only: cli.only,
+            skip: cli.skip,
+            ..Default::default()
+        };
+        match cli.plugin {
+            Some(url) if url.starts_with("http://") || url.starts_with("https://") => {
+                overrides.plugin.url = Some(url);
+            }
+            path => overrides.plugin.path = path,
+        }
+        overrides.plugin.directory = cli.dir;
+        if !cli.lints_paths.is_empty() {
+            overrides.plugin.lints_paths = Some(cli.lints_paths);
+        }
         Ok(Self {
             verbose: cli.verbose,
-            config,
+            config: cli.config,
             dir,
+            offline: cli.offline,
+            overrides,
         })
     }
 
+    /// The config file layered with the environment variables and CLI flags.
+    fn linter_config(&self) -> anyhow::Result<LinterConfig> {
+        LinterConfig::load(self.config.as_deref(), &self.dir, &self.overrides)
+    }
+
     fn validate(&self) -> anyhow::Result<()> {
-        if !self.config.exists() {
+        if let Some(config) = &self.config
+            && !config.exists()
+        {
             return Err(anyhow::anyhow!("Config file does not exist"));
         }
         if !self.dir.exists() {
@@ -107,17 +347,8 @@ impl FinalCli {
 //     }
 // }
 
-// Based off article https://steezeburger.com/2023/03/rust-hierarchical-configuration/
-// fn figment_layered_impl() -> anyhow::Result<CLI> {
-//     let conf: CLI = Figment::new()
-//         .merge(Yaml::file("linter.yaml"))
-//         .merge(Env::prefixed("LINTER_"))
-//         .merge(Serialized::defaults(CLI::parse()))
-//         .extract()?;
-//     Ok(conf)
-// }
-
-fn init_logger(verbose: bool) -> anyhow::Result<()> {
+/// Logs to stdout, or to stderr for commands that write to stdout themselves.
+fn init_logger(verbose: bool, mode: TerminalMode) -> anyhow::Result<()> {
     let level = if verbose {
         LevelFilter::Debug
     } else {
@@ -131,29 +362,79 @@ fn init_logger(verbose: bool) -> anyhow::Result<()> {
         .set_level_color(Level::Trace, Some(Color::Green))
         .set_time_level(LevelFilter::Off)
         .build();
-    TermLogger::init(level, config, TerminalMode::Stdout, ColorChoice::Auto).unwrap();
+    TermLogger::init(level, config, mode, ColorChoice::Auto).unwrap();
     Ok(())
 }
 
 fn main() -> anyhow::Result<()> {
-    // let args = figment_layered_impl()?;
-    // let args = FinalCli::new(args)?;
-    // args.validate()?;
     let args = LinterCommands::parse();
+    let mode = args.terminal_mode();
     match args {
-        LinterCommands::Lint(cli) => {
-            init_logger(cli.verbose)?;
-            let args = FinalCli::new(cli)?;
+        LinterCommands::Lint(lint_cli) => {
+            if let Err(err) = lint_cli.validate() {
+                err.exit();
+            }
+            let args = FinalCli::new(lint_cli.cli)?;
+            init_logger(args.verbose, mode)?;
             args.validate()?;
-            wasm_main(args.config)?;
+            let options = LintOptions {
+                format: lint_cli.format,
+                output: lint_cli.output,
+                baseline: lint_cli.baseline,
+                write_baseline: lint_cli.write_baseline,
+                jobs: lint_cli.jobs as usize,
+                fail_on: lint_cli.fail_on,
+                max_warnings: lint_cli.max_warnings,
+            };
+            let cache = RemoteCache::new(args.offline);
+            if lint_cli.watch {
+                watch_main(args.linter_config()?, &cache, &options)?;
+            } else {
+                wasm_main(args.linter_config()?, &cache, &options)?;
+            }
         }
         LinterCommands::Compare(cli) => {
-            init_logger(cli.verbose)?;
+            init_logger(cli.verbose, mode)?;
+            let config = match cli.config {
+                Some(config) => config,
+                None => discover_compare_config_file(&std::env::current_dir()?)
+                    .ok_or_else(|| anyhow::anyhow!("No linter_config.yaml file found"))?,
+            };
+            if !config.is_file() {
+                return Err(anyhow::anyhow!("Config file does not exist"));
+            }
+            compare_lints_main(config, &RemoteCache::new(cli.offline))?;
+        }
+        LinterCommands::Test(cli) => {
             let args = FinalCli::new(cli)?;
+            init_logger(args.verbose, mode)?;
             args.validate()?;
-            compare_lints_main(args.config)?;
-        } // LinterCommands::Test => todo!(),
-          // LinterCommands::Output => todo!(),
+            test_main(args.linter_config()?, &RemoteCache::new(args.offline))?;
+        }
+        LinterCommands::Output(cli) => {
+            init_logger(cli.verbose, mode)?;
+            output_main(cli)?;
+        }
+        LinterCommands::Schema(cli) => {
+            init_logger(cli.verbose, mode)?;
+            schema_main(cli)?;
+        }
+        LinterCommands::Explain(explain_cli) => {
+            let args = FinalCli::new(explain_cli.cli)?;
+            init_logger(args.verbose, mode)?;
+            args.validate()?;
+            explain_main(
+                args.linter_config()?,
+                &RemoteCache::new(args.offline),
+                &explain_cli.lint,
+            )?;
+        }
+        LinterCommands::Lsp(cli) => {
+            let args = FinalCli::new(cli)?;
+            init_logger(args.verbose, mode)?;
+            args.validate()?;
+            lsp_main(args.linter_config()?, &RemoteCache::new(args.offline))?;
+        }
     }
 
     // lint_main(args)?;
@@ -402,3 +683,52 @@ fn main() -> anyhow::Result<()> {
 //         Err(anyhow::anyhow!("Linting tests failed"))
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint_cli(args: &[&str]) -> LintCli {
+        match LinterCommands::try_parse_from(["linter", "lint"].iter().chain(args)).unwrap() {
+            LinterCommands::Lint(lint_cli) => lint_cli,
+            command => panic!("expected the lint command: {:?}", command),
+        }
+    }
+
+    #[test]
+    fn test_reports_on_stdout_are_not_mixed_with_logs() {
+        let mode = |args: &[&str]| {
+            LinterCommands::try_parse_from(std::iter::once(&"linter").chain(args))
+                .unwrap()
+                .terminal_mode()
+        };
+        for format in ["sarif", "junit", "json"] {
+            assert!(matches!(
+                mode(&["lint", "--format", format, "--verbose"]),
+                TerminalMode::Stderr
+            ));
+        }
+        assert!(matches!(
+            mode(&["output", "--format", "json", "--verbose", "query.graphql"]),
+            TerminalMode::Stderr
+        ));
+        assert!(matches!(
+            mode(&["schema", "--plugin", "plugin.wasm", "--verbose"]),
+            TerminalMode::Stderr
+        ));
+        assert!(matches!(mode(&["lsp"]), TerminalMode::Stderr));
+        assert!(matches!(mode(&["lint"]), TerminalMode::Stdout));
+    }
+
+    #[test]
+    fn test_output_requires_report_format() {
+        assert!(lint_cli(&["--output", "report.txt"]).validate().is_err());
+        assert!(lint_cli(&["--format", "text", "--output", "report.txt"])
+            .validate()
+            .is_err());
+        assert!(lint_cli(&["--format", "json", "--output", "report.json"])
+            .validate()
+            .is_ok());
+        assert!(lint_cli(&["--format", "text"]).validate().is_ok());
+    }
+}
@@ -68,7 +68,7 @@ impl OpenApiAdapter {
             .clone()
             .into();
         route.path = path.to_string();
-        Vertex::Path(route)
+        Vertex::Path(Box::new(route))
     }
 
     fn paths<'a>(&self) -> VertexIterator<'a, Vertex> {
@@ -76,7 +76,7 @@ impl OpenApiAdapter {
             let mut route: Route = x.1.into();
             route.path = x.0.clone().to_string();
             debug!("Route: {:?}", route);
-            Vertex::Path(route)
+            Vertex::Path(Box::new(route))
         });
         Box::new(iter)
     }
@@ -109,12 +109,9 @@ impl<'a> trustfall::provider::Adapter<'a> for OpenApiAdapter {
                     .expect(
                         "unexpected null or other incorrect datatype for Trustfall type 'String!'",
                     );
-                // super::entrypoints::path(path, resolve_info, &self.openapi)
                 Box::new(std::iter::once(self.path(path)))
             }
-            // "Paths" => super::entrypoints::paths(resolve_info, &self.openapi),
             "Paths" => self.paths(),
-            // "Tags" => super::entrypoints::tags(resolve_info, &self.openapi),
             // "Tags" => Box::new(std::iter::once(self.tags())),
             "Tags" => self.tags(),
             _ => {
@@ -1,6 +1,5 @@
 mod adapter_impl;
 mod edges;
-mod entrypoints;
 mod properties;
 mod vertex;
 
@@ -6,7 +6,7 @@ pub enum Vertex {
     AmazonApigatewayIntegration(AmazonApigatewayIntegration),
     Info(openapiv3::Info),
     Operation(Operator),
-    Path(Route),
+    Path(Box<Route>),
     Paths(Vec<Route>),
     Tags(Vec<openapiv3::Tag>),
     Tag(openapiv3::Tag),
@@ -0,0 +1,177 @@
+use clap::ValueEnum;
+
+use crate::{
+    cache::RemoteCache,
+    plugin_config::{CliPluginConfig, LinterConfig, LinterData, PluginData},
+    validate::{load_schema, query_errors},
+    wasm_main::{call_lint_single, load_plugin},
+    OutputCli,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Yaml,
+}
+
+pub fn output_main(cli: OutputCli) -> anyhow::Result<()> {
+    let plugin_data = output_plugin(&cli)?;
+    let query = std::fs::read_to_string(&cli.query)?;
+    let lint = plugin_core::Lint {
+        name: cli.query.display().to_string(),
+        lint: query,
+        args: cli.args.into_iter().collect(),
+        ..Default::default()
+    };
+    let mut plugin = load_plugin(&plugin_data, &RemoteCache::new(cli.offline))?;
+    if let Some(schema) = load_schema(&mut plugin)? {
+        let errors = query_errors(&schema, &lint.lint);
+        if !errors.is_empty() {
+            for err in errors {
+                eprintln!("{}: {}", lint.name, err);
+            }
+            return Err(anyhow::anyhow!("Query is not valid"));
+        }
+    }
+    let rows = call_lint_single(&mut plugin, lint)?;
+    let output = match cli.format {
+        OutputFormat::Table => format_table(&rows),
+        OutputFormat::Json => serde_json::to_string_pretty(&rows)?,
+        OutputFormat::Yaml => serde_yaml::to_string(&rows)?,
+    };
+    println!("{}", output);
+    Ok(())
+}
+
+/// Gets the plugin to run the query with, either from the config file or from the path or url
+/// given on the command line.
+fn output_plugin(cli: &OutputCli) -> anyhow::Result<PluginData> {
+    let mut plugin_data = match cli.plugin.as_deref() {
+        Some(url) if url.starts_with("http://") || url.starts_with("https://") => {
+            PluginData::from_cli_plugin_config(CliPluginConfig {
+                url: Some(url.to_string()),
+                ..Default::default()
+            })?
+        }
+        Some(path) if path.ends_with(".wasm") => {
+            PluginData::from_cli_plugin_config(CliPluginConfig {
+                path: Some(path.to_string()),
+                ..Default::default()
+            })?
+        }
+        name => {
+            let dir = std::env::current_dir()?;
+            let config = LinterConfig::load(cli.config.as_deref(), &dir, &LinterConfig::default())?;
+            let mut plugins = LinterData::from_linter_config(config)?.plugins;
+            match name {
+                Some(name) => plugins
+                    .into_iter()
+                    .find(|plugin| plugin.name == name)
+                    .ok_or_else(|| anyhow::anyhow!("The {} plugin is not configured", name))?,
+                None if plugins.len() == 1 => plugins.remove(0),
+                None => {
+                    return Err(anyhow::anyhow!(
+                        "Config has more than one plugin, specify which one to use with --plugin"
+                    ));
+                }
+            }
+        }
+    };
+    if let Some(dir) = &cli.dir {
+        plugin_data.directory = dir.clone();
+    }
+    Ok(plugin_data)
+}
+
+/// Formats the rows as a table with a column for each output of the query.
+fn format_table(rows: &[serde_json::Value]) -> String {
+    if rows.is_empty() {
+        return String::from("(0 rows)");
+    }
+    let mut columns: Vec<String> = Vec::new();
+    for row in rows {
+        if let Some(row) = row.as_object() {
+            for key in row.keys() {
+                if !columns.contains(key) {
+                    columns.push(key.clone());
+                }
+            }
+        }
+    }
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|column| match row.get(column) {
+                    Some(serde_json::Value::String(value)) => value.clone(),
+                    Some(value) => value.to_string(),
+                    None => String::new(),
+                })
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            cells
+                .iter()
+                .map(|row| row[i].chars().count())
+                .chain(std::iter::once(column.chars().count()))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    let format_row = |row: &[String]| {
+        row.iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<String>>()
+            .join(" | ")
+    };
+    let mut lines = vec![
+        format_row(&columns),
+        widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<String>>()
+            .join("-|-"),
+    ];
+    lines.extend(cells.iter().map(|row| format_row(row)));
+    lines.push(format!("({} rows)", rows.len()));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_format_table() {
+        let rows = vec![
+            json!({"path": "/pets", "method": "GET"}),
+            json!({"path": "/pets/{id}", "method": "DELETE", "timeoutInMillis": 3000}),
+        ];
+        assert_eq!(
+            format_table(&rows),
+            [
+                "method | path       | timeoutInMillis",
+                "-------|------------|----------------",
+                "GET    | /pets      |                ",
+                "DELETE | /pets/{id} | 3000           ",
+                "(2 rows)",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_format_table_no_rows() {
+        assert_eq!(format_table(&[]), "(0 rows)");
+    }
+}
@@ -1,15 +1,230 @@
-use std::path::PathBuf;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
+use figment::{
+    providers::{Env, Format, Serialized, Yaml},
+    Figment,
+};
 use serde::{Deserialize, Serialize};
 
+/// Names of the config files that are discovered, in order of preference.
+const CONFIG_FILE_NAMES: [&str; 2] = ["linter.yaml", "linter_config.yaml"];
+
+/// Name of the config file of the compare command that is discovered.
+const COMPARE_CONFIG_FILE_NAME: &str = "linter_config.yaml";
+
+/// Config keys that can be set with `LINTER_*` environment variables.
+const ENV_KEYS: [&str; 9] = [
+    "path",
+    "url",
+    "sha256",
+    "directory",
+    "lints_paths",
+    "urls",
+    "severity_overrides",
+    "only",
+    "skip",
+];
+
+/// The linter config file. Either a list of `plugins` or the fields of a single plugin at the
+/// top level of the file.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LinterConfig {
+    /// The plugins to run.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plugins: Vec<CliPluginConfig>,
+    /// Single plugin config, kept for config files written before multiple plugins were supported.
+    #[serde(flatten)]
+    pub plugin: CliPluginConfig,
+    /// Severity of lints per lint name, overriding the severity set in the lints files.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub severity_overrides: BTreeMap<String, Severity>,
+    /// Only run the lints with these names or tags, or in the lints files with these names.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub only: Vec<String>,
+    /// Do not run the lints with these names or tags, or in the lints files with these names.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skip: Vec<String>,
+}
+
+impl LinterConfig {
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        if !path.is_file() {
+            return Err(anyhow::anyhow!(
+                "Config file {} does not exist",
+                path.display()
+            ));
+        }
+        Ok(Figment::from(Yaml::file(path)).extract()?)
+    }
+
+    /// Loads the config file, or the config file discovered from the directory, and layers the
+    /// `LINTER_*` environment variables and the overrides from the CLI flags on top of it. Relative
+    /// paths in the config file are relative to the file. When the config file lists plugins, the
+    /// plugin, its directory and its lints cannot be overridden, as there is no single plugin to
+    /// override.
+    pub fn load(
+        config_file: Option<&Path>,
+        dir: &Path,
+        overrides: &LinterConfig,
+    ) -> anyhow::Result<Self> {
+        Self::load_with_env(
+            config_file,
+            dir,
+            Env::prefixed("LINTER_").only(&ENV_KEYS),
+            overrides,
+        )
+    }
+
+    /// Loads the config file, or the config file discovered from the directory, and layers the
+    /// environment variables and the overrides on top of it, with each layer overriding the ones
+    /// before it.
+    fn load_with_env(
+        config_file: Option<&Path>,
+        dir: &Path,
+        env: Env,
+        overrides: &LinterConfig,
+    ) -> anyhow::Result<Self> {
+        let config_file = match config_file {
+            Some(path) => Some(path.to_path_buf()),
+            None => discover_config_file(dir),
+        };
+        let file_config = match config_file {
+            Some(path) => {
+                let mut file_config = Self::from_file(&path)?;
+                file_config.resolve_paths(path.parent().unwrap_or(Path::new("")));
+                file_config
+            }
+            None => Self::default(),
+        };
+        let layers = Figment::new()
+            .merge(env)
+            .merge(Serialized::defaults(overrides));
+        if !file_config.plugins.is_empty() {
+            let overridden = layers.extract::<LinterConfig>()?.plugin.set_fields();
+            if !overridden.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "The config file lists plugins, so {} cannot be set with --plugin, --dir, \
+                     --lints-path or LINTER_* environment variables. Set them for each plugin in \
+                     the config file instead",
+                    overridden.join(", ")
+                ));
+            }
+        }
+        Ok(Figment::new()
+            .merge(Serialized::defaults(file_config))
+            .merge(layers)
+            .extract()?)
+    }
+
+    /// Makes the relative paths in the config relative to the directory.
+    fn resolve_paths(&mut self, dir: &Path) {
+        for plugin in std::iter::once(&mut self.plugin).chain(self.plugins.iter_mut()) {
+            if let Some(path) = plugin.path.as_mut()
+                && Path::new(path).is_relative()
+            {
+                *path = dir.join(&path).display().to_string();
+            }
+            if let Some(directory) = plugin.directory.as_mut()
+                && directory.is_relative()
+            {
+                *directory = dir.join(&directory);
+            }
+            for lints_path in plugin.lints_paths.iter_mut().flatten() {
+                if lints_path.is_relative() {
+                    *lints_path = dir.join(&lints_path);
+                }
+            }
+        }
+    }
+}
+
+/// Finds the config file in the directory or the closest parent directory that has one.
+fn discover_config_file(dir: &Path) -> Option<PathBuf> {
+    discover_file(dir, &CONFIG_FILE_NAMES)
+}
+
+/// Finds the config file of the compare command in the directory or the closest parent directory
+/// that has one.
+pub fn discover_compare_config_file(dir: &Path) -> Option<PathBuf> {
+    discover_file(dir, &[COMPARE_CONFIG_FILE_NAME])
+}
+
+/// Finds the first of the files in the directory or the closest parent directory that has one.
+fn discover_file(dir: &Path, names: &[&str]) -> Option<PathBuf> {
+    dir.ancestors().find_map(|dir| {
+        names
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+    })
+}
+
+/// Selects the lints to run by lint name, tag or the name of the lints file they are in.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LintFilter {
+    /// Only the lints matching one of these are run. All lints are run when it is empty.
+    pub only: Vec<String>,
+    /// The lints matching one of these are not run.
+    pub skip: Vec<String>,
+}
+
+/// How a lint is reported when it fails, from not being run at all to failing the linter.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The lint is not run.
+    Off,
+    Info,
+    Warning,
+    Error,
+}
+
+impl std::str::FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(Severity::Off),
+            "info" => Ok(Severity::Info),
+            "warning" => Ok(Severity::Warning),
+            "error" => Ok(Severity::Error),
+            _ => Err(format!(
+                "invalid severity, expected off, info, warning or error: {}",
+                s
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self {
+            Severity::Off => "off",
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{}", severity)
+    }
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct CliPluginConfig {
+    /// Name of the plugin. Defaults to the file name of the plugin.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
     /// Path to the plugin.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub path: Option<String>,
     /// Url to the plugin.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
+    /// Hex encoded sha256 digest the plugin has to match.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
     /// Directory containing the files to be linted. Defaults to the current directory.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub directory: Option<PathBuf>,
@@ -18,7 +233,48 @@ pub struct CliPluginConfig {
     pub lints_paths: Option<Vec<PathBuf>>,
     /// Urls to the lints files.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub urls: Option<Vec<String>>,
+    pub urls: Option<Vec<RemoteUrl>>,
+}
+
+impl CliPluginConfig {
+    /// Names of the fields that are set.
+    fn set_fields(&self) -> Vec<&'static str> {
+        [
+            ("name", self.name.is_some()),
+            ("path", self.path.is_some()),
+            ("url", self.url.is_some()),
+            ("sha256", self.sha256.is_some()),
+            ("directory", self.directory.is_some()),
+            ("lints_paths", self.lints_paths.is_some()),
+            ("urls", self.urls.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, set)| set.then_some(name))
+        .collect()
+    }
+}
+
+/// Url to a remote file, either on its own or pinned to the sha256 digest of its contents.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum RemoteUrl {
+    Url(String),
+    Pinned { url: String, sha256: String },
+}
+
+impl RemoteUrl {
+    pub fn url(&self) -> &str {
+        match self {
+            RemoteUrl::Url(url) | RemoteUrl::Pinned { url, .. } => url,
+        }
+    }
+
+    pub fn sha256(&self) -> Option<&str> {
+        match self {
+            RemoteUrl::Url(_) => None,
+            RemoteUrl::Pinned { sha256, .. } => Some(sha256),
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -30,7 +286,7 @@ pub struct ComparePluginConfig {
     pub lints_paths: Option<Vec<PathBuf>>,
     /// Urls to the lints files.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub urls: Option<Vec<String>>,
+    pub urls: Option<Vec<RemoteUrl>>,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -41,32 +297,75 @@ pub struct CliComparePluginConfig {
     /// Url to the plugin.
     #[serde(skip_serializing_if = "Option::is_none")]
     url: Option<String>,
+    /// Hex encoded sha256 digest the plugin has to match.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sha256: Option<String>,
     /// Directory containing the files to be linted. Defaults to the current directory.
     #[serde(skip_serializing_if = "Option::is_none")]
     directory: Option<PathBuf>,
 }
 
+#[derive(Debug, Clone)]
 pub enum PluginLocation {
     Path(PathBuf),
     Url(String),
 }
 
+impl std::fmt::Display for PluginLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PluginLocation::Path(path) => write!(f, "{}", path.display()),
+            PluginLocation::Url(url) => write!(f, "{}", url),
+        }
+    }
+}
+
+impl PluginLocation {
+    /// Name derived from the plugin's file name, without the extension.
+    fn default_name(&self) -> String {
+        let file_name = match self {
+            PluginLocation::Path(path) => path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            PluginLocation::Url(url) => url
+                .rsplit('/')
+                .next()
+                .map(|s| s.split_once('.').map_or(s, |(stem, _)| stem).to_string())
+                .unwrap_or_default(),
+        };
+        if file_name.is_empty() {
+            String::from("plugin")
+        } else {
+            file_name
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct PluginData {
+    pub name: String,
     pub plugin: PluginLocation,
+    /// Hex encoded sha256 digest the plugin has to match.
+    pub sha256: Option<String>,
     pub directory: PathBuf,
     pub lints_paths: Vec<PathBuf>,
-    pub urls: Vec<String>,
+    pub urls: Vec<RemoteUrl>,
 }
 
 impl PluginData {
     fn new(
+        name: String,
         plugin: PluginLocation,
+        sha256: Option<String>,
         directory: PathBuf,
         lints_paths: Vec<PathBuf>,
-        urls: Vec<String>,
+        urls: Vec<RemoteUrl>,
     ) -> Self {
         Self {
+            name,
             plugin,
+            sha256,
             directory,
             lints_paths,
             urls,
@@ -83,7 +382,11 @@ impl PluginData {
         };
 
         Ok(Self::new(
+            cli_plugin_config
+                .name
+                .unwrap_or_else(|| plugin.default_name()),
             plugin,
+            cli_plugin_config.sha256,
             cli_plugin_config
                 .directory
                 .unwrap_or(std::env::current_dir().expect("Failed to get current dir")),
@@ -104,7 +407,9 @@ impl PluginData {
         };
 
         Ok(Self::new(
+            plugin.default_name(),
             plugin,
+            cli_plugin_config.sha256,
             cli_plugin_config
                 .directory
                 .unwrap_or(std::env::current_dir().expect("Failed to get current dir")),
@@ -114,9 +419,81 @@ impl PluginData {
     }
 }
 
-struct ComparePluginData {
-    main: PluginData,
-    secondary: PluginData,
+/// The plugins and the lints shared between them from the linter config.
+pub struct LinterData {
+    pub plugins: Vec<PluginData>,
+    /// Paths to the lints files that are not tied to a single plugin.
+    pub lints_paths: Vec<PathBuf>,
+    /// Urls to the lints files that are not tied to a single plugin.
+    pub urls: Vec<RemoteUrl>,
+    /// Severity of lints per lint name.
+    pub severity_overrides: BTreeMap<String, Severity>,
+    pub filter: LintFilter,
+}
+
+impl LinterData {
+    /// Gets the plugins from the linter config, falling back to the single plugin config when no
+    /// `plugins` are listed. With a list of plugins, the top level `lints_paths` and `urls` are
+    /// shared lints and the top level `directory` is used for plugins without a directory.
+    pub fn from_linter_config(config: LinterConfig) -> anyhow::Result<Self> {
+        if config.plugins.is_empty() {
+            return Ok(Self {
+                plugins: vec![PluginData::from_cli_plugin_config(config.plugin)?],
+                lints_paths: Vec::new(),
+                urls: Vec::new(),
+                severity_overrides: config.severity_overrides,
+                filter: LintFilter {
+                    only: config.only,
+                    skip: config.skip,
+                },
+            });
+        }
+        let CliPluginConfig {
+            name,
+            path,
+            url,
+            sha256,
+            directory,
+            lints_paths,
+            urls,
+        } = config.plugin;
+        if name.is_some() || path.is_some() || url.is_some() || sha256.is_some() {
+            return Err(anyhow::anyhow!(
+                "Config has both a list of plugins and a top level plugin"
+            ));
+        }
+        let mut plugins: Vec<PluginData> = Vec::new();
+        for mut plugin_config in config.plugins {
+            if plugin_config.directory.is_none() {
+                plugin_config.directory.clone_from(&directory);
+            }
+            let plugin = PluginData::from_cli_plugin_config(plugin_config)?;
+            if plugins.iter().any(|p| p.name == plugin.name) {
+                return Err(anyhow::anyhow!(
+                    "Plugin name {} is used more than once",
+                    plugin.name
+                ));
+            }
+            plugins.push(plugin);
+        }
+        Ok(Self {
+            plugins,
+            lints_paths: lints_paths.unwrap_or_default(),
+            urls: urls.unwrap_or_default(),
+            severity_overrides: config.severity_overrides,
+            filter: LintFilter {
+                only: config.only,
+                skip: config.skip,
+            },
+        })
+    }
+}
+
+pub struct ComparePluginData {
+    pub main: PluginData,
+    pub secondary: PluginData,
+    pub lints_paths: Vec<PathBuf>,
+    pub urls: Vec<RemoteUrl>,
 }
 
 impl ComparePluginData {
@@ -124,6 +501,317 @@ impl ComparePluginData {
         let main: PluginData = PluginData::from_cli_compare_plugin_config(config.main)?;
         let secondary: PluginData = PluginData::from_cli_compare_plugin_config(config.secondary)?;
 
-        Ok(Self { main, secondary })
+        Ok(Self {
+            main,
+            secondary,
+            lints_paths: config.lints_paths.unwrap_or_default(),
+            urls: config.urls.unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_plugin_config() {
+        let config: LinterConfig = serde_yaml::from_str(
+            r#"
+path: target/wasm32-wasi/debug/plugin_openapi.wasm
+directory: test_files/openapi
+lints_paths:
+  - lints/open_api.yaml
+"#,
+        )
+        .unwrap();
+        let linter_data = LinterData::from_linter_config(config).unwrap();
+        let plugins = linter_data.plugins;
+        assert_eq!(plugins.len(), 1);
+        assert_eq!(plugins[0].name, "plugin_openapi");
+        assert_eq!(plugins[0].lints_paths.len(), 1);
+    }
+
+    #[test]
+    fn test_multiple_plugins_config() {
+        let config: LinterConfig = serde_yaml::from_str(
+            r#"
+plugins:
+  - name: openapi
+    path: target/wasm32-wasi/debug/plugin_openapi.wasm
+    directory: test_files/openapi
+  - url: https://example.com/plugin_terraform.wasm
+    directory: test_files/terraform
+"#,
+        )
+        .unwrap();
+        let linter_data = LinterData::from_linter_config(config).unwrap();
+        let plugins = linter_data.plugins;
+        assert_eq!(plugins.len(), 2);
+        assert_eq!(plugins[0].name, "openapi");
+        assert_eq!(plugins[1].name, "plugin_terraform");
+    }
+
+    #[test]
+    fn test_shared_lints() {
+        let config: LinterConfig = serde_yaml::from_str(
+            r#"
+plugins:
+  - name: openapi
+    path: plugin_openapi.wasm
+  - name: terraform
+    path: plugin_terraform.wasm
+lints_paths:
+  - lints/endpoints.yaml
+"#,
+        )
+        .unwrap();
+        let linter_data = LinterData::from_linter_config(config).unwrap();
+        assert_eq!(linter_data.plugins.len(), 2);
+        assert_eq!(
+            linter_data.lints_paths,
+            vec![PathBuf::from("lints/endpoints.yaml")]
+        );
+    }
+
+    #[test]
+    fn test_pinned_urls() {
+        let config: LinterConfig = serde_yaml::from_str(
+            r#"
+url: https://example.com/plugin_openapi.wasm
+sha256: 9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08
+urls:
+  - https://example.com/lints.json
+  - url: https://example.com/pinned_lints.json
+    sha256: 2c26b46b68ffc68ff99b453c1d30413413422d706483bfa0f98a5e886266e7ae
+"#,
+        )
+        .unwrap();
+        let plugin = LinterData::from_linter_config(config)
+            .unwrap()
+            .plugins
+            .remove(0);
+        assert!(plugin.sha256.is_some());
+        assert_eq!(plugin.urls[0].sha256(), None);
+        assert_eq!(
+            plugin.urls[1].url(),
+            "https://example.com/pinned_lints.json"
+        );
+        assert!(plugin.urls[1].sha256().is_some());
+    }
+
+    #[test]
+    fn test_duplicate_plugin_names() {
+        let config: LinterConfig = serde_yaml::from_str(
+            r#"
+plugins:
+  - name: openapi
+    path: plugin_openapi.wasm
+  - name: openapi
+    path: other_plugin.wasm
+"#,
+        )
+        .unwrap();
+        assert!(LinterData::from_linter_config(config).is_err());
+    }
+
+    #[test]
+    fn test_plugins_and_top_level_plugin() {
+        let config: LinterConfig = serde_yaml::from_str(
+            r#"
+path: plugin_openapi.wasm
+plugins:
+  - path: plugin_terraform.wasm
+"#,
+        )
+        .unwrap();
+        assert!(LinterData::from_linter_config(config).is_err());
+    }
+
+    #[test]
+    fn test_plugins_default_directory() {
+        let config: LinterConfig = serde_yaml::from_str(
+            r#"
+directory: test_files
+plugins:
+  - path: plugin_openapi.wasm
+  - path: plugin_terraform.wasm
+    directory: test_files/terraform
+"#,
+        )
+        .unwrap();
+        let plugins = LinterData::from_linter_config(config).unwrap().plugins;
+        assert_eq!(plugins[0].directory, PathBuf::from("test_files"));
+        assert_eq!(plugins[1].directory, PathBuf::from("test_files/terraform"));
+    }
+
+    #[test]
+    fn test_discovered_config_is_layered() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let dir = root.join("services").join("api");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            root.join("linter.yaml"),
+            r#"
+path: plugins/plugin_openapi.wasm
+lints_paths:
+  - lints/open_api.yaml
+severity_overrides:
+  No DELETE method: warning
+only:
+  - style
+skip:
+  - Endpoints
+"#,
+        )
+        .unwrap();
+        let overrides = LinterConfig {
+            plugin: CliPluginConfig {
+                directory: Some(dir.clone()),
+                ..Default::default()
+            },
+            severity_overrides: [("No PUT method".to_string(), Severity::Error)]
+                .into_iter()
+                .collect(),
+            only: vec!["security".to_string()],
+            ..Default::default()
+        };
+        let config = LinterConfig::load_with_env(None, &dir, no_env(), &overrides).unwrap();
+        assert_eq!(config.only, vec!["security".to_string()]);
+        assert_eq!(config.skip, vec!["Endpoints".to_string()]);
+        assert_eq!(
+            config.plugin.path,
+            Some(
+                root.join("plugins/plugin_openapi.wasm")
+                    .display()
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            config.plugin.lints_paths,
+            Some(vec![root.join("lints/open_api.yaml")])
+        );
+        assert_eq!(config.plugin.directory, Some(dir));
+        assert_eq!(config.severity_overrides.len(), 2);
+        assert_eq!(
+            config.severity_overrides["No DELETE method"],
+            Severity::Warning
+        );
+    }
+
+    #[test]
+    fn test_explicit_config_paths_are_relative_to_the_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_dir = temp_dir.path().join("other");
+        std::fs::create_dir_all(&config_dir).unwrap();
+        let config_file = config_dir.join("linter.yaml");
+        std::fs::write(
+            &config_file,
+            r#"
+plugins:
+  - path: plugins/plugin_openapi.wasm
+    directory: api
+    lints_paths:
+      - lints/open_api.yaml
+  - path: /plugins/plugin_terraform.wasm
+"#,
+        )
+        .unwrap();
+        let config = LinterConfig::load_with_env(
+            Some(&config_file),
+            temp_dir.path(),
+            no_env(),
+            &LinterConfig::default(),
+        )
+        .unwrap();
+        let plugin = &config.plugins[0];
+        assert_eq!(
+            plugin.path,
+            Some(
+                config_dir
+                    .join("plugins/plugin_openapi.wasm")
+                    .display()
+                    .to_string()
+            )
+        );
+        assert_eq!(plugin.directory, Some(config_dir.join("api")));
+        assert_eq!(
+            plugin.lints_paths,
+            Some(vec![config_dir.join("lints/open_api.yaml")])
+        );
+        assert_eq!(
+            config.plugins[1].path,
+            Some("/plugins/plugin_terraform.wasm".to_string())
+        );
+    }
+
+    #[test]
+    fn test_plugins_config_rejects_plugin_overrides() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_file = temp_dir.path().join("linter.yaml");
+        std::fs::write(
+            &config_file,
+            r#"
+directory: test_files
+lints_paths:
+  - lints/shared.yaml
+plugins:
+  - path: plugin_openapi.wasm
+  - path: plugin_terraform.wasm
+    directory: test_files/terraform
+"#,
+        )
+        .unwrap();
+        let load = |overrides: &LinterConfig| {
+            LinterConfig::load_with_env(Some(&config_file), temp_dir.path(), no_env(), overrides)
+        };
+        let config = load(&LinterConfig {
+            only: vec!["security".to_string()],
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(config.plugins.len(), 2);
+        assert_eq!(config.only, vec!["security".to_string()]);
+        assert_eq!(
+            config.plugin.directory,
+            Some(temp_dir.path().join("test_files"))
+        );
+        let err = load(&LinterConfig {
+            plugin: CliPluginConfig {
+                path: Some("other_plugin.wasm".to_string()),
+                directory: Some(PathBuf::from("api")),
+                lints_paths: Some(vec![PathBuf::from("lints/api.yaml")]),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("path, directory, lints_paths cannot be set"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_discover_compare_config_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = temp_dir.path().join("services").join("api");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(temp_dir.path().join("linter.yaml"), "").unwrap();
+        assert_eq!(discover_compare_config_file(&dir), None);
+        std::fs::write(temp_dir.path().join("linter_config.yaml"), "").unwrap();
+        assert_eq!(
+            discover_compare_config_file(&dir),
+            Some(temp_dir.path().join("linter_config.yaml"))
+        );
+    }
+
+    /// Environment provider that ignores the `LINTER_*` variables of the environment the tests
+    /// run in.
+    fn no_env() -> Env {
+        Env::raw().filter(|_| false)
     }
 }
@@ -0,0 +1,255 @@
+use serde::Serialize;
+
+use super::{LintOutcome, LintReport};
+use crate::{plugin_config::Severity, wasm_main::LintDocs};
+
+/// Version of the JSON report. Fields may be added without changing the version, but it is
+/// incremented whenever fields are renamed, removed or change meaning.
+const JSON_REPORT_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize)]
+struct JsonReport<'a> {
+    version: u32,
+    tool: Tool,
+    plugins: Vec<JsonPlugin>,
+    lints: Vec<JsonLint<'a>>,
+    summary: Summary,
+}
+
+#[derive(Debug, Serialize)]
+struct Tool {
+    name: &'static str,
+    version: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonPlugin {
+    name: String,
+    location: String,
+    directory: String,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    /// The lint ran and no rows failed it.
+    Passed,
+    /// Rows failed the lint.
+    Failed,
+    /// The plugin could not run the lint.
+    Error,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonLint<'a> {
+    name: &'a str,
+    plugin: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    compared_plugin: Option<&'a str>,
+    severity: Severity,
+    message: &'a str,
+    #[serde(flatten)]
+    docs: &'a LintDocs,
+    status: Status,
+    duration_ms: f64,
+    rows: &'a [serde_json::Value],
+    /// The message for each row, with the placeholders in the message replaced, or the message with
+    /// the unmet expectation when the lint failed without rows.
+    messages: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unmet_expectation: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'a str>,
+    /// Number of rows that are not reported as they are ignored in the linted sources.
+    suppressed: usize,
+    /// Number of rows that are not reported as they are in the baseline.
+    baselined: usize,
+    /// Rows in the baseline that no longer fail the lint.
+    fixed: &'a [serde_json::Value],
+}
+
+#[derive(Debug, Default, Serialize)]
+struct Summary {
+    /// Whether every lint could be run and none of the lints that fail the linter failed.
+    passed: bool,
+    lints: usize,
+    lints_passed: usize,
+    /// Number of error lints that failed.
+    errors: usize,
+    /// Number of warning lints that failed.
+    warnings: usize,
+    /// Number of info lints that failed.
+    info: usize,
+    /// Number of lints the plugins could not run.
+    plugin_errors: usize,
+    rows: usize,
+    suppressed: usize,
+    baselined: usize,
+    fixed: usize,
+    duration_ms: f64,
+}
+
+impl<'a> From<&'a LintOutcome> for JsonLint<'a> {
+    fn from(outcome: &'a LintOutcome) -> Self {
+        let status = if outcome.error.is_some() {
+            Status::Error
+        } else if outcome.passed() {
+            Status::Passed
+        } else {
+            Status::Failed
+        };
+        Self {
+            name: &outcome.name,
+            plugin: &outcome.plugin,
+            compared_plugin: outcome.compared_plugin.as_deref(),
+            severity: outcome.severity(),
+            message: outcome.message(),
+            docs: &outcome.docs,
+            status,
+            duration_ms: outcome.duration.as_secs_f64() * 1000.0,
+            rows: &outcome.rows,
+            messages: outcome.failure_messages(),
+            unmet_expectation: outcome.unmet_expectation.as_deref(),
+            error: outcome.error.as_deref(),
+            suppressed: outcome.suppressed,
+            baselined: outcome.baselined,
+            fixed: &outcome.fixed,
+        }
+    }
+}
+
+/// Creates a single JSON document with the plugins, the outcome of every lint and a summary.
+pub(super) fn json_report(report: &LintReport) -> anyhow::Result<String> {
+    let lints: Vec<JsonLint> = report.outcomes.iter().map(JsonLint::from).collect();
+    let mut summary = Summary {
+        passed: report.passed,
+        lints: lints.len(),
+        duration_ms: report.duration.as_secs_f64() * 1000.0,
+        ..Default::default()
+    };
+    for lint in &lints {
+        summary.rows += lint.rows.len();
+        summary.suppressed += lint.suppressed;
+        summary.baselined += lint.baselined;
+        summary.fixed += lint.fixed.len();
+        match lint.status {
+            Status::Passed => summary.lints_passed += 1,
+            Status::Error => summary.plugin_errors += 1,
+            Status::Failed => match lint.severity {
+                Severity::Error => summary.errors += 1,
+                Severity::Warning => summary.warnings += 1,
+                Severity::Info | Severity::Off => summary.info += 1,
+            },
+        }
+    }
+    let json_report = JsonReport {
+        version: JSON_REPORT_VERSION,
+        tool: Tool {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+        },
+        plugins: report
+            .plugins
+            .iter()
+            .map(|plugin| JsonPlugin {
+                name: plugin.name.clone(),
+                location: plugin.plugin.to_string(),
+                directory: plugin.directory.display().to_string(),
+            })
+            .collect(),
+        lints,
+        summary,
+    };
+    Ok(serde_json::to_string_pretty(&json_report)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, time::Duration};
+
+    use plugin_core::LintResult;
+    use serde_json::json;
+
+    use super::super::tests::outcome;
+    use super::*;
+
+    #[test]
+    fn test_json_report() {
+        let mut plugin_error = outcome(LintResult::Error("No PATCH method".to_string()), vec![]);
+        let mut unmet = outcome(LintResult::Error("Has a GET method".to_string()), vec![]);
+        unmet.unmet_expectation = Some("expected at least 1 row, got 0".to_string());
+        plugin_error.error = Some("not a legal query".to_string());
+        let mut documented = outcome(
+            LintResult::Error("No DELETE method allowed".to_string()),
+            vec![json!({"path": "/pets/{id}", "method": "DELETE"})],
+        );
+        documented.docs = LintDocs {
+            docs_url: Some("https://example.com/lints/no-delete".to_string()),
+            bad_examples: vec!["delete: {}".to_string()],
+            ..Default::default()
+        };
+        let outcomes = vec![
+            documented,
+            outcome(
+                LintResult::Warning("No PUT method allowed".to_string()),
+                vec![json!({"path": "/pets", "method": "PUT"})],
+            ),
+            outcome(
+                LintResult::Error("No POST method allowed".to_string()),
+                vec![],
+            ),
+            plugin_error,
+            unmet,
+        ];
+        let report = LintReport {
+            plugins: &[],
+            outcomes: &outcomes,
+            locations: &HashMap::new(),
+            duration: Duration::from_millis(50),
+            passed: false,
+            fail_on: Severity::Error,
+            max_warnings: None,
+        };
+        let report: serde_json::Value =
+            serde_json::from_str(&json_report(&report).unwrap()).unwrap();
+        assert_eq!(report["version"], JSON_REPORT_VERSION);
+        assert_eq!(report["lints"][0]["status"], "failed");
+        assert_eq!(report["lints"][0]["severity"], "error");
+        assert_eq!(report["lints"][0]["rows"][0]["method"], "DELETE");
+        assert_eq!(
+            report["lints"][0]["docs_url"],
+            "https://example.com/lints/no-delete"
+        );
+        assert_eq!(report["lints"][0]["bad_examples"], json!(["delete: {}"]));
+        assert!(report["lints"][1].get("docs_url").is_none());
+        assert_eq!(
+            report["lints"][0]["messages"],
+            json!(["No DELETE method allowed"])
+        );
+        assert_eq!(report["lints"][2]["status"], "passed");
+        assert_eq!(report["lints"][3]["status"], "error");
+        assert_eq!(report["lints"][3]["error"], "not a legal query");
+        assert_eq!(report["lints"][4]["status"], "failed");
+        assert_eq!(
+            report["lints"][4]["messages"],
+            json!(["Has a GET method: expected at least 1 row, got 0"])
+        );
+        assert_eq!(
+            report["summary"],
+            json!({
+                "passed": false,
+                "lints": 5,
+                "lints_passed": 1,
+                "errors": 2,
+                "warnings": 1,
+                "info": 0,
+                "plugin_errors": 1,
+                "rows": 2,
+                "suppressed": 0,
+                "baselined": 0,
+                "fixed": 0,
+                "duration_ms": 50.0,
+            })
+        );
+    }
+}
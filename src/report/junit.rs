@@ -0,0 +1,291 @@
+use std::fmt::Write;
+
+use super::{warning_count, LintOutcome};
+use crate::plugin_config::Severity;
+
+/// Escapes the text so it can be used in XML attributes and elements.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[derive(Debug, Default)]
+struct Counts {
+    tests: usize,
+    failures: usize,
+    errors: usize,
+    skipped: usize,
+}
+
+impl Counts {
+    fn add(&mut self, outcome: &LintOutcome, fail_on: Severity) {
+        self.tests += 1;
+        if outcome.error.is_some() {
+            self.errors += 1;
+        } else if outcome.failed() {
+            if outcome.severity() >= fail_on {
+                self.failures += 1;
+            } else {
+                self.skipped += 1;
+            }
+        }
+    }
+
+    fn attributes(&self) -> String {
+        format!(
+            r#"tests="{}" failures="{}" errors="{}" skipped="{}""#,
+            self.tests, self.failures, self.errors, self.skipped
+        )
+    }
+}
+
+/// Creates a JUnit XML report with a test suite per plugin and a test case per lint. Failed lints
+/// with the `fail_on` severity or above are failures and failed lints below it are skipped. With
+/// `max_warnings`, a `linter` test suite has a `max-warnings` test case that fails when more rows
+/// failed warning lints, so that the report fails whenever the lint run fails.
+pub(super) fn junit_report(
+    outcomes: &[LintOutcome],
+    fail_on: Severity,
+    max_warnings: Option<usize>,
+) -> anyhow::Result<String> {
+    let mut plugins: Vec<&str> = Vec::new();
+    for outcome in outcomes {
+        if !plugins.contains(&outcome.plugin.as_str()) {
+            plugins.push(&outcome.plugin);
+        }
+    }
+    let mut total = Counts::default();
+    let mut suites = String::new();
+    for plugin in plugins {
+        let mut counts = Counts::default();
+        let mut cases = String::new();
+        for outcome in outcomes.iter().filter(|outcome| outcome.plugin == plugin) {
+            counts.add(outcome, fail_on);
+            total.add(outcome, fail_on);
+            write_test_case(&mut cases, outcome, fail_on)?;
+        }
+        writeln!(
+            suites,
+            r#"  <testsuite name="{}" {}>"#,
+            escape(plugin),
+            counts.attributes()
+        )?;
+        suites.push_str(&cases);
+        writeln!(suites, "  </testsuite>")?;
+    }
+    if let Some(max_warnings) = max_warnings {
+        let warnings = warning_count(outcomes);
+        let passed = warnings <= max_warnings;
+        let counts = Counts {
+            tests: 1,
+            failures: usize::from(!passed),
+            ..Default::default()
+        };
+        total.tests += counts.tests;
+        total.failures += counts.failures;
+        writeln!(
+            suites,
+            r#"  <testsuite name="{}" {}>"#,
+            env!("CARGO_PKG_NAME"),
+            counts.attributes()
+        )?;
+        if passed {
+            writeln!(
+                suites,
+                r#"    <testcase name="max-warnings" classname="{}"/>"#,
+                env!("CARGO_PKG_NAME")
+            )?;
+        } else {
+            writeln!(
+                suites,
+                r#"    <testcase name="max-warnings" classname="{}">"#,
+                env!("CARGO_PKG_NAME")
+            )?;
+            writeln!(
+                suites,
+                r#"      <failure message="{} rows failed warning lints, more than the {} allowed"/>"#,
+                warnings, max_warnings
+            )?;
+            writeln!(suites, "    </testcase>")?;
+        }
+        writeln!(suites, "  </testsuite>")?;
+    }
+    let mut report = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    writeln!(
+        report,
+        r#"<testsuites name="{}" {}>"#,
+        env!("CARGO_PKG_NAME"),
+        total.attributes()
+    )?;
+    report.push_str(&suites);
+    report.push_str("</testsuites>");
+    Ok(report)
+}
+
+fn write_test_case(
+    xml: &mut String,
+    outcome: &LintOutcome,
+    fail_on: Severity,
+) -> anyhow::Result<()> {
+    let name = escape(&outcome.name);
+    let classname = escape(&outcome.plugin);
+    if let Some(err) = &outcome.error {
+        writeln!(
+            xml,
+            r#"    <testcase name="{}" classname="{}">"#,
+            name, classname
+        )?;
+        writeln!(
+            xml,
+            r#"      <error message="Error in the plugin running lint">{}</error>"#,
+            escape(err)
+        )?;
+        writeln!(xml, "    </testcase>")?;
+    } else if outcome.passed() {
+        writeln!(
+            xml,
+            r#"    <testcase name="{}" classname="{}"/>"#,
+            name, classname
+        )?;
+    } else {
+        let element = if outcome.severity() >= fail_on {
+            "failure"
+        } else {
+            "skipped"
+        };
+        // A lint that failed without rows, as it did not return enough rows, only has the
+        // expectation it did not meet.
+        let rows = if outcome.rows.is_empty() {
+            None
+        } else if outcome.is_templated() {
+            Some(outcome.failure_messages().join("\n"))
+        } else {
+            Some(serde_json::to_string_pretty(&outcome.rows)?)
+        };
+        let details = match (&outcome.unmet_expectation, rows) {
+            (Some(unmet), Some(rows)) => format!("{}\n{}", unmet, rows),
+            (Some(unmet), None) => unmet.clone(),
+            (None, rows) => rows.unwrap_or_default(),
+        };
+        writeln!(
+            xml,
+            r#"    <testcase name="{}" classname="{}">"#,
+            name, classname
+        )?;
+        writeln!(
+            xml,
+            r#"      <{} message="{}">{}</{}>"#,
+            element,
+            escape(outcome.message()),
+            escape(&details),
+            element
+        )?;
+        writeln!(xml, "    </testcase>")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use plugin_core::LintResult;
+    use serde_json::json;
+
+    use super::super::tests::outcome;
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape(r#"<a href="x">&'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&apos;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_junit_report() {
+        let mut terraform = outcome(
+            LintResult::Error("Modules need a version".to_string()),
+            Vec::new(),
+        );
+        terraform.plugin = "terraform".to_string();
+        let outcomes = vec![
+            outcome(
+                LintResult::Error("No DELETE method allowed".to_string()),
+                vec![json!({"path": "/pets/{id}", "method": "DELETE"})],
+            ),
+            terraform,
+            outcome(
+                LintResult::Warning("No PUT method allowed".to_string()),
+                vec![json!({"path": "/pets", "method": "PUT"})],
+            ),
+        ];
+        let report = junit_report(&outcomes, Severity::Error, None).unwrap();
+        assert!(report.starts_with(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"linter\" tests=\"3\" failures=\"1\" errors=\"0\" skipped=\"1\">"
+        ));
+        assert!(report.contains(
+            r#"<testsuite name="openapi" tests="2" failures="1" errors="0" skipped="1">"#
+        ));
+        assert!(report.contains(
+            r#"<testsuite name="terraform" tests="1" failures="0" errors="0" skipped="0">"#
+        ));
+        assert!(report.contains(r#"<failure message="No DELETE method allowed">"#));
+        assert!(report.contains(r#"<skipped message="No PUT method allowed">"#));
+        assert!(report.contains("&quot;method&quot;: &quot;DELETE&quot;"));
+        assert!(!report.contains("max-warnings"));
+    }
+
+    #[test]
+    fn test_junit_report_fail_on_and_max_warnings() {
+        let outcomes = vec![
+            outcome(
+                LintResult::Warning("No PUT method allowed".to_string()),
+                vec![
+                    json!({"path": "/pets", "method": "PUT"}),
+                    json!({"path": "/owners", "method": "PUT"}),
+                ],
+            ),
+            outcome(
+                LintResult::Info("Paths should have a summary".to_string()),
+                vec![json!({"path": "/pets"})],
+            ),
+        ];
+        let report = junit_report(&outcomes, Severity::Warning, None).unwrap();
+        assert!(report.contains(r#"<failure message="No PUT method allowed">"#));
+        assert!(report.contains(r#"<skipped message="Paths should have a summary">"#));
+
+        let report = junit_report(&outcomes, Severity::Error, Some(1)).unwrap();
+        assert!(report.contains(r#"<skipped message="No PUT method allowed">"#));
+        assert!(report.contains(
+            r#"<testsuites name="linter" tests="3" failures="1" errors="0" skipped="2">"#
+        ));
+        assert!(report.contains(
+            r#"<failure message="2 rows failed warning lints, more than the 1 allowed"/>"#
+        ));
+        let report = junit_report(&outcomes, Severity::Error, Some(2)).unwrap();
+        assert!(report.contains(r#"<testcase name="max-warnings" classname="linter"/>"#));
+    }
+
+    #[test]
+    fn test_junit_report_unmet_expectation() {
+        let mut outcome = outcome(
+            LintResult::Error("Modules need a backend".to_string()),
+            Vec::new(),
+        );
+        outcome.unmet_expectation = Some("expected at least 1 row, got 0".to_string());
+        let report = junit_report(&[outcome], Severity::Error, None).unwrap();
+        assert!(report.contains(
+            r#"<failure message="Modules need a backend">expected at least 1 row, got 0</failure>"#
+        ));
+    }
+}
@@ -0,0 +1,437 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use clap::ValueEnum;
+use plugin_core::{find_location, LintResult, SourceLocation};
+use serde::{Deserialize, Serialize};
+use simplelog::{debug, error, info, warn};
+
+use crate::{
+    plugin_config::{PluginData, Severity},
+    wasm_main::LintDocs,
+};
+
+mod json;
+mod junit;
+mod sarif;
+
+/// Format of the report of the lint results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    /// Log the failed lints and their rows
+    #[default]
+    Text,
+    /// SARIF 2.1.0 log
+    Sarif,
+    /// JUnit XML report
+    Junit,
+    /// Versioned JSON report
+    Json,
+}
+
+/// The plugins and lint outcomes of a lint run.
+pub struct LintReport<'a> {
+    pub plugins: &'a [PluginData],
+    pub outcomes: &'a [LintOutcome],
+    /// Locations of the blocks of the linted sources per plugin, from the plugins with the
+    /// `locations` export.
+    pub locations: &'a HashMap<String, Vec<SourceLocation>>,
+    /// Time taken to load the plugins and run the lints.
+    pub duration: Duration,
+    /// Whether the lint run passed, see [`lints_passed`].
+    pub passed: bool,
+    /// Lowest severity of the lints that fail the lint run when they fail.
+    pub fail_on: Severity,
+    /// Number of rows that can fail warning lints before the lint run fails.
+    pub max_warnings: Option<usize>,
+}
+
+/// The outcome of running a single lint.
+#[derive(Debug, Clone)]
+pub struct LintOutcome {
+    pub name: String,
+    /// Name of the plugin that ran the lint.
+    pub plugin: String,
+    /// Name of the plugin that ran the compared lint, if it was set.
+    pub compared_plugin: Option<String>,
+    /// The message of the lint with its severity.
+    pub output: LintResult,
+    pub docs: LintDocs,
+    /// Arguments of the lint query, which the placeholders in the message can refer to.
+    pub args: HashMap<String, serde_json::Value>,
+    /// Rows that failed the lint.
+    pub rows: Vec<serde_json::Value>,
+    /// Why the rows returned by the lint did not meet what the lint expects, when the failed rows
+    /// alone do not tell.
+    pub unmet_expectation: Option<String>,
+    /// Error from the plugin if the lint could not be run.
+    pub error: Option<String>,
+    /// Time taken to run the lint.
+    pub duration: Duration,
+    /// Number of rows that are not reported as they are ignored in the linted sources.
+    pub suppressed: usize,
+    /// Number of rows that are not reported as they are in the baseline.
+    pub baselined: usize,
+    /// Rows in the baseline that no longer fail the lint.
+    pub fixed: Vec<serde_json::Value>,
+}
+
+impl LintOutcome {
+    /// Whether the lint ran and returned the rows it expects.
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.rows.is_empty() && self.unmet_expectation.is_none()
+    }
+
+    /// Whether the lint ran but did not return the rows it expects.
+    pub fn failed(&self) -> bool {
+        self.error.is_none() && !self.passed()
+    }
+
+    pub fn message(&self) -> &str {
+        match &self.output {
+            LintResult::Info(message)
+            | LintResult::Warning(message)
+            | LintResult::Error(message) => message,
+        }
+    }
+
+    /// The message for a row that failed the lint, with the `{name}` placeholders in the message
+    /// replaced by the row's output with that name, or else the lint's argument with that name.
+    /// Placeholders that match neither are kept as they are.
+    pub fn row_message(&self, row: &serde_json::Value) -> String {
+        let template = self.message();
+        let mut message = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            message.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let value = rest.find('}').and_then(|end| {
+                let name = &rest[1..end];
+                let value = row.get(name).or_else(|| self.args.get(name))?;
+                Some((end, value))
+            });
+            match value {
+                Some((end, serde_json::Value::String(value))) => {
+                    message.push_str(value);
+                    rest = &rest[end + 1..];
+                }
+                Some((end, value)) => {
+                    message.push_str(&value.to_string());
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    message.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        message.push_str(rest);
+        message
+    }
+
+    /// The message for each row that failed the lint, or the message with the unmet expectation
+    /// when the lint failed without rows, such as when it expects rows and none are returned.
+    pub fn failure_messages(&self) -> Vec<String> {
+        match &self.unmet_expectation {
+            Some(unmet) if self.rows.is_empty() => {
+                vec![format!("{}: {}", self.message(), unmet)]
+            }
+            _ => self.rows.iter().map(|row| self.row_message(row)).collect(),
+        }
+    }
+
+    /// Whether the message has placeholders that are replaced for the rows that failed the lint.
+    pub fn is_templated(&self) -> bool {
+        self.rows
+            .iter()
+            .any(|row| self.row_message(row) != self.message())
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self.output {
+            LintResult::Info(_) => Severity::Info,
+            LintResult::Warning(_) => Severity::Warning,
+            LintResult::Error(_) => Severity::Error,
+        }
+    }
+}
+
+/// The file and line of the block the row of the lint came from, looked up in the locations of the
+/// plugin that ran the lint and then of the plugin that ran the compared lint. The file is in the
+/// directory of the plugin, or is the directory when the plugin lints a single file.
+pub fn row_location(
+    outcome: &LintOutcome,
+    row: &serde_json::Value,
+    locations: &HashMap<String, Vec<SourceLocation>>,
+    directories: &HashMap<String, PathBuf>,
+) -> Option<(PathBuf, u32)> {
+    std::iter::once(&outcome.plugin)
+        .chain(&outcome.compared_plugin)
+        .find_map(|plugin| {
+            let location = find_location(locations.get(plugin)?, row)?;
+            let directory = directories.get(plugin)?;
+            let path = if directory.is_file() {
+                directory.clone()
+            } else {
+                directory.join(&location.path)
+            };
+            Some((path, location.line))
+        })
+}
+
+/// Whether every lint could be run, none of the lints with the `fail_on` severity or above failed
+/// and no more rows than `max_warnings` failed warning lints.
+pub fn lints_passed(
+    outcomes: &[LintOutcome],
+    fail_on: Severity,
+    max_warnings: Option<usize>,
+) -> bool {
+    let failed = outcomes.iter().any(|outcome| {
+        outcome.error.is_some() || (outcome.failed() && outcome.severity() >= fail_on)
+    });
+    !failed && max_warnings.is_none_or(|max_warnings| warning_count(outcomes) <= max_warnings)
+}
+
+/// Counts the rows that failed warning lints. A warning lint that failed without rows, as it did
+/// not return enough rows, counts as one.
+pub fn warning_count(outcomes: &[LintOutcome]) -> usize {
+    outcomes
+        .iter()
+        .filter(|outcome| outcome.severity() == Severity::Warning)
+        .map(|outcome| outcome.rows.len().max(usize::from(outcome.failed())))
+        .sum()
+}
+
+/// Counts the lints that passed, failed with each severity, or could not be run.
+pub fn summary(outcomes: &[LintOutcome]) -> String {
+    let not_run = outcomes.iter().filter(|o| o.error.is_some()).count();
+    let passed = outcomes.iter().filter(|o| o.passed()).count();
+    let failed = |severity| {
+        outcomes
+            .iter()
+            .filter(|o| o.failed() && o.severity() == severity)
+            .count()
+    };
+    format!(
+        "{} lints: {} passed, {} failed with errors, {} failed with warnings, {} failed with info, {} could not be run",
+        outcomes.len(),
+        passed,
+        failed(Severity::Error),
+        failed(Severity::Warning),
+        failed(Severity::Info),
+        not_run
+    )
+}
+
+/// Logs the outcomes in the text format or writes the report to the output file, or stdout if no
+/// file is given.
+pub fn write_report(
+    format: ReportFormat,
+    report: &LintReport,
+    output: Option<&Path>,
+) -> anyhow::Result<()> {
+    let report = match format {
+        ReportFormat::Text => {
+            log_outcomes(report.outcomes);
+            return Ok(());
+        }
+        ReportFormat::Sarif => sarif::sarif_report(report)?,
+        ReportFormat::Junit => {
+            junit::junit_report(report.outcomes, report.fail_on, report.max_warnings)?
+        }
+        ReportFormat::Json => json::json_report(report)?,
+    };
+    match output {
+        Some(path) => std::fs::write(path, report)?,
+        None => println!("{}", report),
+    }
+    Ok(())
+}
+
+fn log_outcomes(outcomes: &[LintOutcome]) {
+    for outcome in outcomes {
+        if let Some(err) = &outcome.error {
+            error!("Error in the plugin running lint: {}", outcome.name);
+            error!("{}", err);
+        } else if outcome.failed() {
+            // A templated message is logged for every row, with the rows themselves only logged
+            // in verbose mode.
+            let templated = outcome.is_templated();
+            let mut messages = if templated {
+                outcome
+                    .rows
+                    .iter()
+                    .map(|row| outcome.row_message(row))
+                    .collect()
+            } else {
+                vec![outcome.message().to_string()]
+            };
+            if let Some(unmet) = &outcome.unmet_expectation {
+                messages.push(format!("{}: {}", outcome.name, unmet));
+            }
+            for message in messages {
+                match outcome.severity() {
+                    Severity::Error => error!("{}", message),
+                    Severity::Warning => warn!("{}", message),
+                    Severity::Info | Severity::Off => info!("{}", message),
+                }
+            }
+            if let Some(docs_url) = &outcome.docs.docs_url {
+                info!("See {}", docs_url);
+            }
+            let rows = serde_json::to_string_pretty(&outcome.rows).unwrap();
+            if templated {
+                debug!("{}", rows);
+            } else if !outcome.rows.is_empty() {
+                println!("{}", rows);
+            }
+        }
+        if !outcome.fixed.is_empty() {
+            info!(
+                "{} row(s) in the baseline for {} no longer occur and can be removed",
+                outcome.fixed.len(),
+                outcome.name
+            );
+            println!("{}", serde_json::to_string_pretty(&outcome.fixed).unwrap());
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    pub(crate) fn outcome(output: LintResult, rows: Vec<serde_json::Value>) -> LintOutcome {
+        LintOutcome {
+            name: "No DELETE method".to_string(),
+            plugin: "openapi".to_string(),
+            compared_plugin: None,
+            output,
+            docs: LintDocs::default(),
+            args: HashMap::new(),
+            rows,
+            unmet_expectation: None,
+            error: None,
+            duration: Duration::from_millis(12),
+            suppressed: 0,
+            baselined: 0,
+            fixed: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_summary() {
+        let rows = vec![json!({"path": "/pets/{id}", "method": "DELETE"})];
+        let mut not_run = outcome(LintResult::Error("No DELETE".to_string()), Vec::new());
+        not_run.error = Some("invalid query".to_string());
+        assert_eq!(
+            summary(&[
+                outcome(LintResult::Error("No DELETE".to_string()), Vec::new()),
+                outcome(LintResult::Error("No DELETE".to_string()), rows.clone()),
+                outcome(LintResult::Warning("No DELETE".to_string()), rows.clone()),
+                outcome(LintResult::Info("No DELETE".to_string()), rows),
+                not_run,
+            ]),
+            "5 lints: 1 passed, 1 failed with errors, 1 failed with warnings, 1 failed with info, 1 could not be run"
+        );
+    }
+
+    #[test]
+    fn test_row_message() {
+        let mut outcome = outcome(
+            LintResult::Warning(
+                "{path} {method} has no timeout (got {timeoutInMillis}, max {max}) {missing} {"
+                    .to_string(),
+            ),
+            Vec::new(),
+        );
+        outcome.args.insert("max".to_string(), json!(29000));
+        let row = json!({"path": "/pets/{id}", "method": "GET", "timeoutInMillis": null});
+        assert_eq!(
+            outcome.row_message(&row),
+            "/pets/{id} GET has no timeout (got null, max 29000) {missing} {"
+        );
+        outcome.rows = vec![row];
+        assert!(outcome.is_templated());
+        outcome.output = LintResult::Warning("Timeouts should be set".to_string());
+        assert!(!outcome.is_templated());
+    }
+
+    #[test]
+    fn test_lints_passed() {
+        let rows = vec![json!({"path": "/pets/{id}", "method": "DELETE"})];
+        assert!(lints_passed(
+            &[
+                outcome(LintResult::Error("No DELETE".to_string()), Vec::new()),
+                outcome(LintResult::Warning("No DELETE".to_string()), rows.clone()),
+            ],
+            Severity::Error,
+            None
+        ));
+        assert!(!lints_passed(
+            &[outcome(LintResult::Error("No DELETE".to_string()), rows)],
+            Severity::Error,
+            None
+        ));
+    }
+
+    #[test]
+    fn test_fail_on() {
+        let rows = vec![json!({"path": "/pets/{id}", "method": "DELETE"})];
+        let outcomes = [
+            outcome(LintResult::Info("No DELETE".to_string()), rows.clone()),
+            outcome(LintResult::Warning("No DELETE".to_string()), Vec::new()),
+        ];
+        assert!(lints_passed(&outcomes, Severity::Warning, None));
+        assert!(!lints_passed(&outcomes, Severity::Info, None));
+        let outcomes = [outcome(LintResult::Warning("No DELETE".to_string()), rows)];
+        assert!(lints_passed(&outcomes, Severity::Error, None));
+        assert!(!lints_passed(&outcomes, Severity::Warning, None));
+    }
+
+    #[test]
+    fn test_max_warnings() {
+        let row = json!({"path": "/pets/{id}", "method": "DELETE"});
+        let outcomes = [
+            outcome(
+                LintResult::Warning("No DELETE".to_string()),
+                vec![row.clone(), row.clone()],
+            ),
+            outcome(
+                LintResult::Warning("No DELETE".to_string()),
+                vec![row.clone()],
+            ),
+            outcome(LintResult::Info("No DELETE".to_string()), vec![row]),
+        ];
+        assert_eq!(warning_count(&outcomes), 3);
+        assert!(lints_passed(&outcomes, Severity::Error, Some(3)));
+        assert!(!lints_passed(&outcomes, Severity::Error, Some(2)));
+    }
+
+    #[test]
+    fn test_unmet_expectation_fails() {
+        let mut outcome = outcome(LintResult::Warning("No backend".to_string()), Vec::new());
+        outcome.unmet_expectation = Some("expected at least 1 row, got 0".to_string());
+        assert!(outcome.failed());
+        assert_eq!(warning_count(std::slice::from_ref(&outcome)), 1);
+        assert!(!lints_passed(
+            std::slice::from_ref(&outcome),
+            Severity::Warning,
+            None
+        ));
+        assert!(summary(&[outcome]).contains("1 failed with warnings"));
+    }
+
+    #[test]
+    fn test_plugin_error_fails() {
+        let mut outcome = outcome(LintResult::Warning("No DELETE".to_string()), Vec::new());
+        outcome.error = Some("not a legal query".to_string());
+        assert!(!lints_passed(&[outcome], Severity::Error, None));
+    }
+}
@@ -0,0 +1,433 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use super::{row_location, LintOutcome, LintReport};
+use crate::plugin_config::Severity;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+#[derive(Debug, Serialize)]
+struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run>,
+}
+
+#[derive(Debug, Serialize)]
+struct Run {
+    tool: Tool,
+    invocations: Vec<Invocation>,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Serialize)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    id: String,
+    name: String,
+    short_description: Message,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    full_description: Option<Message>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help: Option<Message>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help_uri: Option<String>,
+    default_configuration: Configuration,
+}
+
+#[derive(Debug, Serialize)]
+struct Configuration {
+    level: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Invocation {
+    execution_successful: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tool_execution_notifications: Vec<Notification>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Notification {
+    level: &'static str,
+    message: Message,
+    associated_rule: RuleReference,
+}
+
+#[derive(Debug, Serialize)]
+struct RuleReference {
+    id: String,
+    index: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: String,
+    rule_index: usize,
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+    properties: ResultProperties,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<Region>,
+}
+
+#[derive(Debug, Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: u32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResultProperties {
+    plugin: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    compared_plugin: Option<String>,
+    row: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+struct Message {
+    text: String,
+}
+
+fn level(outcome: &LintOutcome) -> &'static str {
+    match outcome.severity() {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info | Severity::Off => "note",
+    }
+}
+
+/// The description and rationale of the lint, if it has either.
+fn full_description(outcome: &LintOutcome) -> Option<Message> {
+    let paragraphs: Vec<&str> = [&outcome.docs.description, &outcome.docs.rationale]
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .collect();
+    (!paragraphs.is_empty()).then(|| Message {
+        text: paragraphs.join("\n\n"),
+    })
+}
+
+/// The path as a relative URI with forward slashes, relative to the current directory when it is an
+/// absolute path in it.
+fn artifact_uri(path: &Path) -> String {
+    let current_dir = std::env::current_dir().unwrap_or_default();
+    let path = path.strip_prefix(&current_dir).unwrap_or(path);
+    let uri = path.to_string_lossy().replace('\\', "/");
+    uri.strip_prefix("./").unwrap_or(&uri).to_string()
+}
+
+/// Where the row came from: the file and line of its block, or else the directory or file linted
+/// by the plugin that ran the lint.
+fn location(
+    outcome: &LintOutcome,
+    row: &serde_json::Value,
+    report: &LintReport,
+    directories: &HashMap<String, PathBuf>,
+) -> Vec<Location> {
+    let (path, region) = match row_location(outcome, row, report.locations, directories) {
+        Some((path, line)) => (path, Some(Region { start_line: line })),
+        None => match directories.get(&outcome.plugin) {
+            Some(directory) => (directory.clone(), None),
+            None => return Vec::new(),
+        },
+    };
+    vec![Location {
+        physical_location: PhysicalLocation {
+            artifact_location: ArtifactLocation {
+                uri: artifact_uri(&path),
+            },
+            region,
+        },
+    }]
+}
+
+/// Creates a SARIF log with a rule per lint and a result per row that failed a lint, located at
+/// the block the row came from when the plugin exports the locations of its blocks.
+pub(super) fn sarif_report(report: &LintReport) -> anyhow::Result<String> {
+    let outcomes = report.outcomes;
+    let directories: HashMap<String, PathBuf> = report
+        .plugins
+        .iter()
+        .map(|plugin_data| (plugin_data.name.clone(), plugin_data.directory.clone()))
+        .collect();
+    let directories = &directories;
+    let rules = outcomes
+        .iter()
+        .map(|outcome| Rule {
+            id: outcome.name.clone(),
+            name: outcome.name.clone(),
+            short_description: Message {
+                text: outcome.message().to_string(),
+            },
+            full_description: full_description(outcome),
+            help: outcome
+                .docs
+                .remediation
+                .clone()
+                .map(|text| Message { text }),
+            help_uri: outcome.docs.docs_url.clone(),
+            default_configuration: Configuration {
+                level: level(outcome),
+            },
+        })
+        .collect();
+    let notifications: Vec<Notification> = outcomes
+        .iter()
+        .enumerate()
+        .filter_map(|(index, outcome)| {
+            outcome.error.as_ref().map(|err| Notification {
+                level: "error",
+                message: Message {
+                    text: format!("Error in the plugin running lint: {}", err),
+                },
+                associated_rule: RuleReference {
+                    id: outcome.name.clone(),
+                    index,
+                },
+            })
+        })
+        .collect();
+    // A lint that failed without rows, as it did not return enough rows, has a single result.
+    let unmet_results = outcomes
+        .iter()
+        .enumerate()
+        .filter(|(_, outcome)| outcome.failed() && outcome.rows.is_empty())
+        .map(|(index, outcome)| SarifResult {
+            rule_id: outcome.name.clone(),
+            rule_index: index,
+            level: level(outcome),
+            message: Message {
+                text: outcome.failure_messages().join("\n"),
+            },
+            locations: location(outcome, &serde_json::Value::Null, report, directories),
+            properties: ResultProperties {
+                plugin: outcome.plugin.clone(),
+                compared_plugin: outcome.compared_plugin.clone(),
+                row: serde_json::Value::Null,
+            },
+        });
+    let results = outcomes
+        .iter()
+        .enumerate()
+        .flat_map(|(index, outcome)| {
+            outcome.rows.iter().map(move |row| SarifResult {
+                rule_id: outcome.name.clone(),
+                rule_index: index,
+                level: level(outcome),
+                message: Message {
+                    text: if outcome.is_templated() {
+                        outcome.row_message(row)
+                    } else {
+                        format!("{}: {}", outcome.message(), row)
+                    },
+                },
+                locations: location(outcome, row, report, directories),
+                properties: ResultProperties {
+                    plugin: outcome.plugin.clone(),
+                    compared_plugin: outcome.compared_plugin.clone(),
+                    row: row.clone(),
+                },
+            })
+        })
+        .chain(unmet_results)
+        .collect();
+    let log = SarifLog {
+        schema: SARIF_SCHEMA,
+        version: SARIF_VERSION,
+        runs: vec![Run {
+            tool: Tool {
+                driver: Driver {
+                    name: env!("CARGO_PKG_NAME"),
+                    version: env!("CARGO_PKG_VERSION"),
+                    information_uri: env!("CARGO_PKG_REPOSITORY"),
+                    rules,
+                },
+            },
+            invocations: vec![Invocation {
+                execution_successful: notifications.is_empty(),
+                tool_execution_notifications: notifications,
+            }],
+            results,
+        }],
+    };
+    Ok(serde_json::to_string_pretty(&log)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use plugin_core::{LintResult, SourceLocation};
+    use serde_json::json;
+
+    use super::super::tests::outcome;
+    use super::*;
+    use crate::{
+        plugin_config::{PluginData, PluginLocation},
+        wasm_main::LintDocs,
+    };
+
+    fn report<'a>(
+        plugins: &'a [PluginData],
+        outcomes: &'a [LintOutcome],
+        locations: &'a HashMap<String, Vec<SourceLocation>>,
+    ) -> LintReport<'a> {
+        LintReport {
+            plugins,
+            outcomes,
+            locations,
+            duration: Duration::from_millis(50),
+            passed: false,
+            fail_on: Severity::Error,
+            max_warnings: None,
+        }
+    }
+
+    #[test]
+    fn test_sarif_report() {
+        let mut documented = outcome(
+            LintResult::Error("No DELETE method allowed".to_string()),
+            vec![json!({"path": "/pets/{id}", "method": "DELETE"})],
+        );
+        documented.docs = LintDocs {
+            description: Some("Checks that no operation uses DELETE".to_string()),
+            rationale: Some("Resources are archived instead".to_string()),
+            remediation: Some("Use a POST to the archive endpoint".to_string()),
+            docs_url: Some("https://example.com/lints/no-delete".to_string()),
+            ..Default::default()
+        };
+        let mut unmet = outcome(LintResult::Info("Has a GET method".to_string()), Vec::new());
+        unmet.unmet_expectation = Some("expected at least 1 row, got 0".to_string());
+        let outcomes = vec![
+            outcome(
+                LintResult::Warning("No PUT method allowed".to_string()),
+                Vec::new(),
+            ),
+            documented,
+            unmet,
+        ];
+        let locations = HashMap::new();
+        let report = report(&[], &outcomes, &locations);
+        let report: serde_json::Value =
+            serde_json::from_str(&sarif_report(&report).unwrap()).unwrap();
+        assert_eq!(report["version"], "2.1.0");
+        let run = &report["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 3);
+        assert_eq!(
+            run["tool"]["driver"]["rules"][0]["defaultConfiguration"]["level"],
+            "warning"
+        );
+        let rule = &run["tool"]["driver"]["rules"][1];
+        assert_eq!(
+            rule["fullDescription"]["text"],
+            "Checks that no operation uses DELETE\n\nResources are archived instead"
+        );
+        assert_eq!(rule["help"]["text"], "Use a POST to the archive endpoint");
+        assert_eq!(rule["helpUri"], "https://example.com/lints/no-delete");
+        assert!(run["tool"]["driver"]["rules"][0]
+            .get("fullDescription")
+            .is_none());
+        assert_eq!(run["invocations"][0]["executionSuccessful"], true);
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["ruleIndex"], 1);
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(results[0]["properties"]["row"]["method"], "DELETE");
+        assert!(results[0]["locations"].as_array().unwrap().is_empty());
+        assert_eq!(results[1]["ruleIndex"], 2);
+        assert_eq!(
+            results[1]["message"]["text"],
+            "Has a GET method: expected at least 1 row, got 0"
+        );
+    }
+
+    #[test]
+    fn test_sarif_locations() {
+        let plugins = vec![PluginData {
+            name: "openapi".to_string(),
+            plugin: PluginLocation::Path(PathBuf::from("openapi.wasm")),
+            sha256: None,
+            directory: PathBuf::from("./api"),
+            lints_paths: Vec::new(),
+            urls: Vec::new(),
+        }];
+        let locations = HashMap::from([(
+            "openapi".to_string(),
+            vec![SourceLocation {
+                fields: json!({"path": "/pets/{id}", "method": "DELETE"})
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+                path: PathBuf::from("pets").join("openapi.yaml"),
+                line: 12,
+            }],
+        )]);
+        let outcomes = vec![outcome(
+            LintResult::Error("No DELETE method allowed".to_string()),
+            vec![
+                json!({"path": "/pets/{id}", "method": "DELETE"}),
+                json!({"path": "/owners/{id}", "method": "DELETE"}),
+            ],
+        )];
+        let report = report(&plugins, &outcomes, &locations);
+        let report: serde_json::Value =
+            serde_json::from_str(&sarif_report(&report).unwrap()).unwrap();
+        let results = &report["runs"][0]["results"];
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"],
+            json!({
+                "artifactLocation": {"uri": "api/pets/openapi.yaml"},
+                "region": {"startLine": 12}
+            })
+        );
+        assert_eq!(
+            results[1]["locations"][0]["physicalLocation"],
+            json!({"artifactLocation": {"uri": "api"}})
+        );
+    }
+}
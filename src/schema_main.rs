@@ -0,0 +1,301 @@
+use std::{collections::BTreeMap, fmt::Write, sync::Arc};
+
+use clap::ValueEnum;
+use serde::Deserialize;
+use trustfall::{execute_query, FieldValue, Schema, SchemaAdapter, TryIntoStruct};
+
+use crate::{
+    cache::RemoteCache,
+    plugin_config::{CliPluginConfig, PluginData},
+    wasm_main::instantiate_plugin,
+    SchemaCli,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SchemaFormat {
+    /// The GraphQL schema as the plugin exports it
+    #[default]
+    Graphql,
+    /// A reference page of the entry points and types
+    Markdown,
+}
+
+pub fn schema_main(cli: SchemaCli) -> anyhow::Result<()> {
+    let plugin_config = if cli.plugin.starts_with("http://") || cli.plugin.starts_with("https://") {
+        CliPluginConfig {
+            url: Some(cli.plugin.clone()),
+            ..Default::default()
+        }
+    } else {
+        CliPluginConfig {
+            path: Some(cli.plugin.clone()),
+            ..Default::default()
+        }
+    };
+    let plugin_data = PluginData::from_cli_plugin_config(plugin_config)?;
+    let mut plugin = instantiate_plugin(&plugin_data, &RemoteCache::new(cli.offline))?;
+    if !plugin.function_exists("schema") {
+        return Err(anyhow::anyhow!(
+            "The {} plugin does not export its schema",
+            plugin_data.name
+        ));
+    }
+    let schema_text = plugin.call::<Option<&str>, String>("schema", None)?;
+    let output = match cli.format {
+        SchemaFormat::Graphql => schema_text,
+        SchemaFormat::Markdown => schema_markdown(&plugin_data.name, &Schema::parse(schema_text)?)?,
+    };
+    println!("{}", output);
+    Ok(())
+}
+
+const ENTRYPOINTS_QUERY: &str = r#"
+{
+    Entrypoint {
+        name @output
+        docs @output
+        to_many @output
+        target {
+            target: name @output
+        }
+        parameter @fold {
+            parameter_names: name @output
+            parameter_types: type @output
+            parameter_defaults: default @output
+            parameter_docs: docs @output
+        }
+    }
+}
+"#;
+
+const VERTEX_TYPES_QUERY: &str = r#"
+{
+    VertexType {
+        name @output
+        docs @output
+        property @fold {
+            property_names: name @output
+            property_types: type @output
+            property_docs: docs @output
+        }
+    }
+}
+"#;
+
+const EDGES_QUERY: &str = r#"
+{
+    VertexType {
+        vertex_type: name @output
+        edge {
+            name @output
+            docs @output
+            to_many @output
+            target {
+                target: name @output
+            }
+            parameter @fold {
+                parameter_names: name @output
+                parameter_types: type @output
+                parameter_defaults: default @output
+                parameter_docs: docs @output
+            }
+        }
+    }
+}
+"#;
+
+#[derive(Debug, Deserialize)]
+struct EdgeRow {
+    #[serde(default)]
+    vertex_type: Option<String>,
+    name: String,
+    docs: Option<String>,
+    to_many: bool,
+    target: String,
+    parameter_names: Vec<String>,
+    parameter_types: Vec<String>,
+    parameter_defaults: Vec<Option<String>>,
+    parameter_docs: Vec<Option<String>>,
+}
+
+impl EdgeRow {
+    fn target_type(&self) -> String {
+        if self.to_many {
+            format!("[{}]", self.target)
+        } else {
+            self.target.clone()
+        }
+    }
+
+    fn parameters(&self) -> Vec<(&str, &str, Option<&str>, Option<&str>)> {
+        (0..self.parameter_names.len())
+            .map(|i| {
+                (
+                    self.parameter_names[i].as_str(),
+                    self.parameter_types[i].as_str(),
+                    self.parameter_defaults[i].as_deref(),
+                    self.parameter_docs[i].as_deref(),
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct VertexTypeRow {
+    name: String,
+    docs: Option<String>,
+    property_names: Vec<String>,
+    property_types: Vec<String>,
+    property_docs: Vec<Option<String>>,
+}
+
+/// Runs the query over the schema itself and deserializes each row.
+fn query_schema<T: for<'de> Deserialize<'de>>(
+    schema: &Schema,
+    query: &str,
+) -> anyhow::Result<Vec<T>> {
+    let schema_schema = Schema::parse(SchemaAdapter::schema_text())?;
+    let adapter = Arc::new(SchemaAdapter::new(schema));
+    execute_query(
+        &schema_schema,
+        adapter,
+        query,
+        BTreeMap::<Arc<str>, FieldValue>::new(),
+    )?
+    .map(|row| Ok(row.try_into_struct()?))
+    .collect()
+}
+
+/// Formats the docs so they fit in a table cell.
+fn table_cell(docs: Option<&str>) -> String {
+    docs.unwrap_or_default()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .replace('|', "\\|")
+}
+
+fn write_parameters(markdown: &mut String, edge: &EdgeRow) -> anyhow::Result<()> {
+    let parameters = edge.parameters();
+    if parameters.is_empty() {
+        return Ok(());
+    }
+    writeln!(markdown, "| Parameter | Type | Default | Description |")?;
+    writeln!(markdown, "|-----------|------|---------|-------------|")?;
+    for (name, parameter_type, default, docs) in parameters {
+        writeln!(
+            markdown,
+            "| `{}` | `{}` | {} | {} |",
+            name,
+            parameter_type,
+            default
+                .map(|default| format!("`{}`", default))
+                .unwrap_or_default(),
+            table_cell(docs)
+        )?;
+    }
+    writeln!(markdown)?;
+    Ok(())
+}
+
+/// Renders a reference page of the entry points and vertex types with their properties, edges
+/// and parameters, using the doc strings of the schema.
+fn schema_markdown(plugin_name: &str, schema: &Schema) -> anyhow::Result<String> {
+    let mut entrypoints: Vec<EdgeRow> = query_schema(schema, ENTRYPOINTS_QUERY)?;
+    entrypoints.sort_by(|a, b| a.name.cmp(&b.name));
+    let mut vertex_types: Vec<VertexTypeRow> = query_schema(schema, VERTEX_TYPES_QUERY)?;
+    vertex_types.sort_by(|a, b| a.name.cmp(&b.name));
+    let edges: Vec<EdgeRow> = query_schema(schema, EDGES_QUERY)?;
+
+    let mut markdown = String::new();
+    writeln!(markdown, "# {} schema", plugin_name)?;
+    writeln!(markdown)?;
+    writeln!(markdown, "## Entry points")?;
+    writeln!(markdown)?;
+    for entrypoint in &entrypoints {
+        writeln!(
+            markdown,
+            "### `{}`: `{}`",
+            entrypoint.name,
+            entrypoint.target_type()
+        )?;
+        writeln!(markdown)?;
+        if let Some(docs) = &entrypoint.docs {
+            writeln!(markdown, "{}", docs.trim())?;
+            writeln!(markdown)?;
+        }
+        write_parameters(&mut markdown, entrypoint)?;
+    }
+    writeln!(markdown, "## Types")?;
+    writeln!(markdown)?;
+    for vertex_type in &vertex_types {
+        writeln!(markdown, "### `{}`", vertex_type.name)?;
+        writeln!(markdown)?;
+        if let Some(docs) = &vertex_type.docs {
+            writeln!(markdown, "{}", docs.trim())?;
+            writeln!(markdown)?;
+        }
+        if !vertex_type.property_names.is_empty() {
+            writeln!(markdown, "| Property | Type | Description |")?;
+            writeln!(markdown, "|----------|------|-------------|")?;
+            for i in 0..vertex_type.property_names.len() {
+                writeln!(
+                    markdown,
+                    "| `{}` | `{}` | {} |",
+                    vertex_type.property_names[i],
+                    vertex_type.property_types[i],
+                    table_cell(vertex_type.property_docs[i].as_deref())
+                )?;
+            }
+            writeln!(markdown)?;
+        }
+        let type_edges: Vec<&EdgeRow> = edges
+            .iter()
+            .filter(|edge| edge.vertex_type.as_deref() == Some(vertex_type.name.as_str()))
+            .collect();
+        if !type_edges.is_empty() {
+            writeln!(markdown, "| Edge | Type | Description |")?;
+            writeln!(markdown, "|------|------|-------------|")?;
+            for edge in &type_edges {
+                writeln!(
+                    markdown,
+                    "| `{}` | `{}` | {} |",
+                    edge.name,
+                    edge.target_type(),
+                    table_cell(edge.docs.as_deref())
+                )?;
+            }
+            writeln!(markdown)?;
+            for edge in type_edges
+                .iter()
+                .filter(|edge| !edge.parameter_names.is_empty())
+            {
+                writeln!(markdown, "Parameters of `{}`:", edge.name)?;
+                writeln!(markdown)?;
+                write_parameters(&mut markdown, edge)?;
+            }
+        }
+    }
+    Ok(markdown.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_markdown() {
+        let schema = Schema::parse(include_str!("../plugin_openapi/src/schema.graphql")).unwrap();
+        let markdown = schema_markdown("openapi", &schema).unwrap();
+        assert!(markdown.starts_with("# openapi schema\n\n## Entry points\n"));
+        assert!(markdown.contains("### `Path`: `Path`\n\nOpenAPI doc path\n"));
+        assert!(markdown.contains("| `path` | `String!` |  |  |\n"));
+        assert!(markdown.contains("\n\n### `Operation`\n\n| Property |"));
+        assert!(markdown.contains(
+            "| `ignoredLints` | `[String!]!` | Lints ignored with the `x-linter-ignore` extension"
+        ));
+        assert!(markdown.contains("| `operations` | `[Operation]` |  |\n"));
+        assert!(!markdown.contains("\n\n\n"));
+    }
+}
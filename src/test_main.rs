@@ -0,0 +1,200 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use extism::Plugin;
+use serde::{Deserialize, Serialize};
+use simplelog::{error, info, warn};
+
+use crate::{
+    cache::RemoteCache,
+    plugin_config::{LinterConfig, LinterData},
+    wasm_main::{call_lint_single, linter_lints, load_plugin},
+};
+
+/// A fixture a lint is tested against and the rows it is expected to return.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LintTest {
+    /// Directory or file the plugin running `lint` is given as its contents.
+    pub directory: PathBuf,
+    /// Rows `lint` is expected to return. Defaults to no rows.
+    #[serde(default)]
+    pub expected: Vec<serde_json::Value>,
+    /// Directory or file the plugin running `compared_lint` is given as its contents.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compared_directory: Option<PathBuf>,
+    /// Rows `compared_lint` is expected to return. Defaults to no rows.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub compared_expected: Vec<serde_json::Value>,
+}
+
+/// Plugins loaded per plugin name and fixture directory.
+type FixturePlugins = HashMap<(String, PathBuf), Plugin>;
+
+pub fn test_main(config: LinterConfig, cache: &RemoteCache) -> anyhow::Result<()> {
+    let linter_data = LinterData::from_linter_config(config)?;
+    let lints = linter_lints(&linter_data, cache)?;
+    let mut plugins = FixturePlugins::new();
+    let mut passed = 0;
+    let mut failed = 0;
+    let mut untested = 0;
+    for lint in lints {
+        if lint.tests.is_empty() {
+            untested += 1;
+            continue;
+        }
+        let plugin_name = lint
+            .plugin
+            .clone()
+            .expect("plugin is set when merging the lints");
+        let compared_plugin_name = lint
+            .compared_plugin
+            .clone()
+            .unwrap_or_else(|| plugin_name.clone());
+        let mut failures = Vec::new();
+        for test in &lint.tests {
+            let result = run_fixture(
+                &mut plugins,
+                &linter_data,
+                cache,
+                &plugin_name,
+                &test.directory,
+                lint.convert_to_plugin_lint().unwrap(),
+            );
+            if let Some(failure) = check_fixture(&test.directory, &test.expected, result) {
+                failures.push(failure);
+            }
+            if let Some(compared_directory) = &test.compared_directory {
+                let Some((_, compared_lint)) = lint.convert_to_plugin_lint_with_compared() else {
+                    failures.push(format!(
+                        "{}: lint has no compared lint to test",
+                        compared_directory.display()
+                    ));
+                    continue;
+                };
+                let result = run_fixture(
+                    &mut plugins,
+                    &linter_data,
+                    cache,
+                    &compared_plugin_name,
+                    compared_directory,
+                    compared_lint,
+                );
+                if let Some(failure) =
+                    check_fixture(compared_directory, &test.compared_expected, result)
+                {
+                    failures.push(failure);
+                }
+            }
+        }
+        if failures.is_empty() {
+            info!("PASS {}", lint.name);
+            passed += 1;
+        } else {
+            error!("FAIL {}", lint.name);
+            for failure in failures {
+                println!("{}", failure);
+            }
+            failed += 1;
+        }
+    }
+    if untested > 0 {
+        warn!("{} lint(s) have no tests", untested);
+    }
+    info!("{} passed, {} failed", passed, failed);
+    if failed > 0 {
+        return Err(anyhow::anyhow!("Lint tests failed"));
+    }
+    Ok(())
+}
+
+/// Runs the lint with the named plugin given the fixture as its contents.
+fn run_fixture(
+    plugins: &mut FixturePlugins,
+    linter_data: &LinterData,
+    cache: &RemoteCache,
+    plugin_name: &str,
+    directory: &Path,
+    lint: plugin_core::Lint,
+) -> anyhow::Result<Vec<serde_json::Value>> {
+    let key = (plugin_name.to_string(), directory.to_path_buf());
+    if !plugins.contains_key(&key) {
+        let mut plugin_data = linter_data
+            .plugins
+            .iter()
+            .find(|plugin| plugin.name == plugin_name)
+            .ok_or_else(|| anyhow::anyhow!("The {} plugin is not configured", plugin_name))?
+            .clone();
+        plugin_data.directory = directory.to_path_buf();
+        plugins.insert(key.clone(), load_plugin(&plugin_data, cache)?);
+    }
+    call_lint_single(plugins.get_mut(&key).unwrap(), lint)
+}
+
+/// Describes why the fixture failed, if it did.
+fn check_fixture(
+    directory: &Path,
+    expected: &[serde_json::Value],
+    result: anyhow::Result<Vec<serde_json::Value>>,
+) -> Option<String> {
+    match result {
+        Ok(rows) => {
+            diff_rows(expected, &rows).map(|diff| format!("{}:\n{}", directory.display(), diff))
+        }
+        Err(e) => Some(format!("{}: {}", directory.display(), e)),
+    }
+}
+
+/// Compares the rows a lint returned with the expected rows, ignoring the order of the rows.
+/// Returns the missing rows prefixed with `-` and the unexpected rows prefixed with `+`.
+fn diff_rows(expected: &[serde_json::Value], actual: &[serde_json::Value]) -> Option<String> {
+    let mut diff = Vec::new();
+    for row in expected {
+        if !actual.contains(row) {
+            diff.push(format!("  - {}", row));
+        }
+    }
+    for row in actual {
+        if !expected.contains(row) {
+            diff.push(format!("  + {}", row));
+        }
+    }
+    if diff.is_empty() {
+        None
+    } else {
+        Some(diff.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_diff_rows_ignores_order() {
+        let expected = vec![json!({"path": "/pets"}), json!({"path": "/pets/{petId}"})];
+        let actual = vec![json!({"path": "/pets/{petId}"}), json!({"path": "/pets"})];
+        assert_eq!(diff_rows(&expected, &actual), None);
+    }
+
+    #[test]
+    fn test_diff_rows_no_rows_expected() {
+        let actual = vec![json!({"path": "/pets", "method": "DELETE"})];
+        assert_eq!(
+            diff_rows(&[], &actual),
+            Some(r#"  + {"method":"DELETE","path":"/pets"}"#.to_string())
+        );
+    }
+
+    #[test]
+    fn test_diff_rows_missing_row() {
+        let expected = vec![json!({"path": "/pets"})];
+        assert_eq!(
+            diff_rows(&expected, &[]),
+            Some(r#"  - {"path":"/pets"}"#.to_string())
+        );
+    }
+}
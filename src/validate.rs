@@ -0,0 +1,135 @@
+use extism::Plugin;
+use trustfall::Schema;
+use trustfall_core::{frontend::error::FrontendError, graphql_query::error::ParseError};
+
+/// Gets the schema from the plugin's `schema` export, if the plugin has one.
+pub fn load_schema(plugin: &mut Plugin) -> anyhow::Result<Option<Schema>> {
+    if !plugin.function_exists("schema") {
+        return Ok(None);
+    }
+    let schema_text = plugin.call::<Option<&str>, String>("schema", None)?;
+    Ok(Some(Schema::parse(schema_text)?))
+}
+
+/// Parses the query with the schema and describes each error, prefixed with its line and column in
+/// the query when it is known.
+pub fn query_errors(schema: &Schema, query: &str) -> Vec<String> {
+    match trustfall_core::frontend::parse(schema, query) {
+        Ok(_) => Vec::new(),
+        Err(err) => describe_error(&err),
+    }
+}
+
+fn describe_error(err: &FrontendError) -> Vec<String> {
+    if let FrontendError::MultipleErrors(errors) = err {
+        return errors.0.iter().flat_map(describe_error).collect();
+    }
+    match error_position(err) {
+        Some((line, column)) => vec![format!("{}:{}: {}", line, column, err)],
+        None => vec![err.to_string()],
+    }
+}
+
+/// The line and column of the error in the query. Only parse errors have a position.
+fn error_position(err: &FrontendError) -> Option<(usize, usize)> {
+    let FrontendError::ParseError(err) = err else {
+        return None;
+    };
+    let pos = match err {
+        ParseError::InvalidGraphQL(err) => {
+            return err.positions().next().map(|pos| (pos.line, pos.column))
+        }
+        ParseError::UnrecognizedDirective(_, pos)
+        | ParseError::UnsupportedDirectivePosition(_, _, pos)
+        | ParseError::MissingRequiredDirectiveArgument(_, _, pos)
+        | ParseError::UnrecognizedDirectiveArgument(_, _, pos)
+        | ParseError::DuplicatedDirectiveArgument(_, _, pos)
+        | ParseError::InappropriateTypeForDirectiveArgument(_, _, pos)
+        | ParseError::FilterExpectsListNotString(_, _, pos)
+        | ParseError::InvalidFieldArgument(_, _, _, pos)
+        | ParseError::DocumentContainsNonInlineFragments(pos)
+        | ParseError::MultipleOperationsInDocument(pos)
+        | ParseError::MultipleQueryRoots(pos)
+        | ParseError::UnsupportedQueryRoot(_, pos)
+        | ParseError::DirectiveNotInsideQueryRoot(_, pos)
+        | ParseError::DocumentNotAQuery(pos)
+        | ParseError::UnsupportedFilterOperator(_, pos)
+        | ParseError::InvalidFilterOperandName(_, _, pos)
+        | ParseError::UnsupportedTransformOperator(_, pos)
+        | ParseError::InvalidOutputName(_, _, pos)
+        | ParseError::InvalidTagName(_, _, pos)
+        | ParseError::UnsupportedSyntax(_, pos)
+        | ParseError::NestedTypeCoercion(pos)
+        | ParseError::TypeCoercionWithSiblingFields(pos)
+        | ParseError::UnsupportedDuplicatedDirective(_, pos)
+        | ParseError::DuplicatedEdgeParameter(_, _, pos)
+        | ParseError::VariableDefinitionInQuery(pos)
+        | ParseError::OtherError(_, pos) => pos,
+        // Parse errors added in later versions of Trustfall are reported without a position.
+        _ => return None,
+    };
+    Some((pos.line, pos.column))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r#"
+schema {
+    query: RootSchemaQuery
+}
+directive @filter(op: String!, value: [String!]) repeatable on FIELD | INLINE_FRAGMENT
+directive @output(name: String) on FIELD
+
+type RootSchemaQuery {
+    Paths: [Path!]!
+}
+
+type Path {
+    path: String!
+}
+"#;
+
+    #[test]
+    fn test_valid_query() {
+        let schema = Schema::parse(SCHEMA).unwrap();
+        assert!(query_errors(&schema, "{ Paths { path @output } }").is_empty());
+    }
+
+    #[test]
+    fn test_unknown_directive_position() {
+        let schema = Schema::parse(SCHEMA).unwrap();
+        let errors = query_errors(&schema, "{\n  Paths {\n    path @outptu\n  }\n}");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("3:10: "), "{}", errors[0]);
+    }
+
+    #[test]
+    fn test_syntax_error_position() {
+        let schema = Schema::parse(SCHEMA).unwrap();
+        let errors = query_errors(&schema, "{\n  Paths {\n    path @output\n");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("4:"), "{}", errors[0]);
+    }
+
+    #[test]
+    fn test_unknown_field() {
+        let schema = Schema::parse(SCHEMA).unwrap();
+        let errors = query_errors(&schema, "{ Paths { method @output } }");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("method"), "{}", errors[0]);
+    }
+
+    #[test]
+    fn test_openapi_lints_are_valid() {
+        let schema = Schema::parse(include_str!("../plugin_openapi/src/schema.graphql")).unwrap();
+        let lints: serde_yaml::Value =
+            serde_yaml::from_str(&std::fs::read_to_string("lints/open_api_lints.yaml").unwrap())
+                .unwrap();
+        for lint in lints["lints"].as_sequence().unwrap() {
+            let query = lint["lint"].as_str().unwrap();
+            assert!(query_errors(&schema, query).is_empty(), "{}", query);
+        }
+    }
+}
@@ -1,38 +1,447 @@
 use extism::{convert::Json, Manifest, Plugin, Wasm};
+use plugin_core::{AvailableFiles, Files, PluginMetadata, SourceLocation, PLUGIN_API_VERSION};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use simplelog::{error, warn};
-use std::{collections::HashMap, path::PathBuf};
+use simplelog::{debug, info, warn};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
 
-use crate::plugin_config::{CliPluginConfig, PluginData, PluginLocation};
+use crate::{
+    baseline::Baseline,
+    cache::{verify_sha256, RemoteCache},
+    files::collect_files,
+    plugin_config::{
+        LintFilter, LinterConfig, LinterData, PluginData, PluginLocation, RemoteUrl, Severity,
+    },
+    report::{lints_passed, warning_count, write_report, LintOutcome, LintReport, ReportFormat},
+    test_main::LintTest,
+    validate::{load_schema, query_errors},
+};
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 struct LintConfig {
+    /// Name of the group of lints in the file, which can be used to select them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
     lints: Vec<LintData>,
 }
 
+/// Documentation of a lint, shown by `linter explain` and included in the reports.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintDocs {
+    /// What the lint checks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Why the lint matters.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rationale: Option<String>,
+    /// How to fix the rows that fail the lint.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
+    /// Link to more documentation of the lint.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub docs_url: Option<String>,
+    /// Sources that pass the lint.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub good_examples: Vec<String>,
+    /// Sources that fail the lint.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bad_examples: Vec<String>,
+}
+
+/// What the rows returned by a lint have to be for the lint to pass. For a compared lint these are
+/// the rows that are returned by only one of its two queries.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Expect {
+    /// No rows are returned.
+    #[default]
+    Empty,
+    /// At least one row is returned.
+    NonEmpty,
+    /// The number of rows is within the bounds.
+    Count {
+        min: Option<usize>,
+        max: Option<usize>,
+    },
+    /// The rows are the listed rows, in any order.
+    Equal(Vec<serde_json::Value>),
+}
+
+impl Expect {
+    /// Checks the rows returned by a lint and returns the rows that fail the lint, with why the
+    /// lint failed when the failing rows alone do not tell.
+    pub fn check(&self, rows: Vec<serde_json::Value>) -> (Vec<serde_json::Value>, Option<String>) {
+        match self {
+            Expect::Empty => (rows, None),
+            Expect::NonEmpty if rows.is_empty() => {
+                (rows, Some("expected at least 1 row, got 0".to_string()))
+            }
+            Expect::NonEmpty => (Vec::new(), None),
+            Expect::Count { max: Some(max), .. } if rows.len() > *max => {
+                let unmet = format!("expected at most {} rows, got {}", max, rows.len());
+                (rows, Some(unmet))
+            }
+            Expect::Count { min: Some(min), .. } if rows.len() < *min => (
+                Vec::new(),
+                Some(format!(
+                    "expected at least {} rows, got {}",
+                    min,
+                    rows.len()
+                )),
+            ),
+            Expect::Count { .. } => (Vec::new(), None),
+            Expect::Equal(expected) => {
+                let missing: Vec<serde_json::Value> = expected
+                    .iter()
+                    .filter(|row| !rows.contains(row))
+                    .cloned()
+                    .collect();
+                let mut failed: Vec<serde_json::Value> = rows
+                    .into_iter()
+                    .filter(|row| !expected.contains(row))
+                    .collect();
+                let unexpected = failed.len();
+                failed.extend(missing.iter().cloned());
+                let unmet = (!failed.is_empty()).then(|| {
+                    format!(
+                        "{} rows were not expected and {} expected rows are missing",
+                        unexpected,
+                        missing.len()
+                    )
+                });
+                (failed, unmet)
+            }
+        }
+    }
+
+    fn validate(&self, lint_name: &str) -> anyhow::Result<()> {
+        match self {
+            Expect::Count {
+                min: None,
+                max: None,
+            } => Err(anyhow::anyhow!(
+                "Lint {} expects a count without a min or max",
+                lint_name
+            )),
+            Expect::Count {
+                min: Some(min),
+                max: Some(max),
+            } if min > max => Err(anyhow::anyhow!(
+                "Lint {} expects a count with a min above its max",
+                lint_name
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Which rows of a lint and its compared lint have to match.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompareMode {
+    /// Every row of each lint matches a row of the other.
+    #[default]
+    Equal,
+    /// Every row of the lint matches a row of the compared lint.
+    Subset,
+    /// Every row of the compared lint matches a row of the lint.
+    Superset,
+}
+
+/// Rewrites a string output so that values that are written differently by the two lints of a
+/// compared lint match.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Normalizer {
+    /// Removes the quotes around the value, e.g. `"GET"` becomes `GET`.
+    TrimQuotes,
+    Lowercase,
+    Uppercase,
+    /// Removes the trailing slashes of a path, except for the root path.
+    StripTrailingSlash,
+    /// Replaces the matches of the regex with the replacement, which can refer to capture groups
+    /// as `$1` or `${name}`.
+    RegexReplace {
+        pattern: String,
+        #[serde(default)]
+        replacement: String,
+    },
+    /// Replaces the names of path parameters with `{}`, e.g. `/pets/{petId}` becomes `/pets/{}`.
+    PathTemplate,
+}
+
+impl Normalizer {
+    fn compile(&self) -> anyhow::Result<CompiledNormalizer<'_>> {
+        Ok(match self {
+            Normalizer::RegexReplace {
+                pattern,
+                replacement,
+            } => CompiledNormalizer::RegexReplace(Regex::new(pattern)?, replacement),
+            normalizer => CompiledNormalizer::Other(normalizer),
+        })
+    }
+}
+
+/// A [`Normalizer`] with its regex compiled.
+enum CompiledNormalizer<'a> {
+    RegexReplace(Regex, &'a str),
+    Other(&'a Normalizer),
+}
+
+impl CompiledNormalizer<'_> {
+    fn apply(&self, value: &str) -> String {
+        match self {
+            CompiledNormalizer::RegexReplace(regex, replacement) => {
+                regex.replace_all(value, *replacement).into_owned()
+            }
+            CompiledNormalizer::Other(Normalizer::TrimQuotes) => {
+                value.trim_matches(|c| c == '"' || c == '\'').to_string()
+            }
+            CompiledNormalizer::Other(Normalizer::Lowercase) => value.to_lowercase(),
+            CompiledNormalizer::Other(Normalizer::Uppercase) => value.to_uppercase(),
+            CompiledNormalizer::Other(Normalizer::StripTrailingSlash) => {
+                let trimmed = value.trim_end_matches('/');
+                if trimmed.is_empty() && !value.is_empty() {
+                    "/".to_string()
+                } else {
+                    trimmed.to_string()
+                }
+            }
+            CompiledNormalizer::Other(Normalizer::PathTemplate) => {
+                let mut path = String::with_capacity(value.len());
+                let mut in_parameter = false;
+                for c in value.chars() {
+                    match c {
+                        '{' if !in_parameter => {
+                            in_parameter = true;
+                            path.push('{');
+                        }
+                        '}' if in_parameter => {
+                            in_parameter = false;
+                            path.push('}');
+                        }
+                        _ if in_parameter => {}
+                        c => path.push(c),
+                    }
+                }
+                path
+            }
+            CompiledNormalizer::Other(Normalizer::RegexReplace { .. }) => {
+                unreachable!("regexes are compiled")
+            }
+        }
+    }
+}
+
+/// How the rows of a lint are matched with the rows of its compared lint.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Compare {
+    #[serde(default)]
+    pub mode: CompareMode,
+    /// Outputs the rows are matched on. Defaults to all the outputs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keys: Vec<String>,
+    /// Names of outputs of the compared lint mapped to the names of the lint's outputs they match.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub field_map: BTreeMap<String, String>,
+    /// Normalizers applied in order to the outputs with the name, after `field_map`, of the rows
+    /// of both lints before they are matched.
+    #[serde(
+        default,
+        skip_serializing_if = "BTreeMap::is_empty",
+        with = "serde_yaml::with::singleton_map_recursive"
+    )]
+    pub normalize: BTreeMap<String, Vec<Normalizer>>,
+}
+
+impl Compare {
+    fn compile_normalizers(&self) -> anyhow::Result<Vec<(&String, Vec<CompiledNormalizer<'_>>)>> {
+        self.normalize
+            .iter()
+            .map(|(field, normalizers)| {
+                let normalizers = normalizers
+                    .iter()
+                    .map(Normalizer::compile)
+                    .collect::<anyhow::Result<_>>()?;
+                Ok((field, normalizers))
+            })
+            .collect()
+    }
+
+    /// The part of the row that is matched with the rows of the other lint.
+    fn key(
+        &self,
+        row: &serde_json::Value,
+        compared: bool,
+        normalizers: &[(&String, Vec<CompiledNormalizer>)],
+    ) -> serde_json::Value {
+        let serde_json::Value::Object(fields) = row else {
+            return row.clone();
+        };
+        let mut fields = fields.clone();
+        if compared {
+            for (from, to) in &self.field_map {
+                if let Some(value) = fields.remove(from) {
+                    fields.insert(to.clone(), value);
+                }
+            }
+        }
+        for (field, field_normalizers) in normalizers {
+            if let Some(serde_json::Value::String(value)) = fields.get_mut(*field) {
+                for normalizer in field_normalizers {
+                    *value = normalizer.apply(value);
+                }
+            }
+        }
+        if !self.keys.is_empty() {
+            fields = self
+                .keys
+                .iter()
+                .map(|key| {
+                    let value = fields.get(key).cloned();
+                    (key.clone(), value.unwrap_or_default())
+                })
+                .collect();
+        }
+        serde_json::Value::Object(fields)
+    }
+
+    /// Returns the rows of the lint and of the compared lint that do not match a row of the other
+    /// and have to, with the lint they are missing from in their `missing_from` field. The rows
+    /// are returned as the lints returned them, without the field map and normalizers applied.
+    pub fn unmatched_rows(
+        &self,
+        rows: &[serde_json::Value],
+        compared_rows: &[serde_json::Value],
+    ) -> anyhow::Result<Vec<serde_json::Value>> {
+        let normalizers = self.compile_normalizers()?;
+        let keys: Vec<serde_json::Value> = rows
+            .iter()
+            .map(|row| self.key(row, false, &normalizers))
+            .collect();
+        let compared_keys: Vec<serde_json::Value> = compared_rows
+            .iter()
+            .map(|row| self.key(row, true, &normalizers))
+            .collect();
+        let mut unmatched = Vec::new();
+        if self.mode != CompareMode::Superset {
+            for (row, key) in rows.iter().zip(&keys) {
+                if !compared_keys.contains(key) {
+                    unmatched.push(missing_from(row, "compared_lint"));
+                }
+            }
+        }
+        if self.mode != CompareMode::Subset {
+            for (row, key) in compared_rows.iter().zip(&compared_keys) {
+                if !keys.contains(key) {
+                    unmatched.push(missing_from(row, "lint"));
+                }
+            }
+        }
+        Ok(unmatched)
+    }
+}
+
+fn missing_from(row: &serde_json::Value, lint: &str) -> serde_json::Value {
+    let mut row = row.clone();
+    if let serde_json::Value::Object(fields) = &mut row {
+        fields.insert("missing_from".to_string(), lint.into());
+    }
+    row
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LintData {
     pub name: String,
-    lint: String,
+    pub lint: String,
     #[serde(default)]
-    args: HashMap<String, serde_json::Value>,
+    pub args: HashMap<String, serde_json::Value>,
+    /// Message reported when the lint fails, with the severity set in `severity`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Severity of the lint's `message`. Defaults to error.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+    /// Shorthand for a `message` with the warning severity.
     pub warning: Option<String>,
+    /// Shorthand for a `message` with the error severity.
     pub error: Option<String>,
-    compared_lint: Option<String>,
-    compared_args: Option<HashMap<String, serde_json::Value>>,
+    pub compared_lint: Option<String>,
+    pub compared_args: Option<HashMap<String, serde_json::Value>>,
+    /// How the rows of the lint are matched with the rows of the compared lint.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compare: Option<Compare>,
+    /// Name of the plugin that runs `lint`. Defaults to the plugin the lint file belongs to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<String>,
+    /// Name of the plugin that runs `compared_lint`. Defaults to `plugin`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compared_plugin: Option<String>,
+    /// Fixtures the lint is tested against with `linter test`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tests: Vec<LintTest>,
+    /// Tags to select the lint with, e.g. `security` or `style`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Name of the lints file the lint is in.
+    #[serde(skip)]
+    pub category: Option<String>,
+    #[serde(flatten)]
+    pub docs: LintDocs,
+    /// What the returned rows have to be for the lint to pass. Defaults to no rows.
+    #[serde(default, with = "serde_yaml::with::singleton_map")]
+    pub expect: Expect,
 }
 
 impl LintData {
-    fn convert_to_plugin_lint(&self) -> Option<plugin_core::Lint> {
+    pub fn severity(&self) -> Severity {
+        match (self.severity, &self.warning) {
+            (Some(severity), _) => severity,
+            (None, Some(_)) => Severity::Warning,
+            (None, None) => Severity::Error,
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        self.message
+            .as_deref()
+            .or(self.warning.as_deref())
+            .or(self.error.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// The message of the lint with its severity. Lints that are off are not run, so they are
+    /// only reported as info when they are run anyway.
+    pub fn output(&self) -> plugin_core::LintResult {
+        let message = self.message().to_string();
+        match self.severity() {
+            Severity::Off | Severity::Info => plugin_core::LintResult::Info(message),
+            Severity::Warning => plugin_core::LintResult::Warning(message),
+            Severity::Error => plugin_core::LintResult::Error(message),
+        }
+    }
+
+    /// Changes the severity of the lint, keeping its message.
+    pub fn set_severity(&mut self, severity: Severity) {
+        if self.message.is_none() {
+            self.message = self.warning.take().or(self.error.take());
+        }
+        self.severity = Some(severity);
+    }
+
+    pub fn convert_to_plugin_lint(&self) -> Option<plugin_core::Lint> {
         Some(plugin_core::Lint {
             name: self.name.clone(),
             lint: self.lint.clone(),
             args: self.args.clone(),
-            output: if self.warning.is_some() {
-                plugin_core::LintResult::Warning(self.warning.clone().unwrap())
-            } else {
-                plugin_core::LintResult::Error(self.error.clone().unwrap())
-            },
+            output: self.output(),
         })
     }
 
@@ -44,25 +453,24 @@ impl LintData {
                 name: self.name.clone(),
                 lint: self.lint.clone(),
                 args: self.args.clone(),
-                output: if self.warning.is_some() {
-                    plugin_core::LintResult::Warning(self.warning.clone().unwrap())
-                } else {
-                    plugin_core::LintResult::Error(self.error.clone().unwrap())
-                },
+                output: self.output(),
             },
             plugin_core::Lint {
                 name: self.name.clone(),
                 lint: self.compared_lint.clone()?,
                 args: self.compared_args.clone().unwrap_or_default(),
-                output: if self.warning.is_some() {
-                    plugin_core::LintResult::Warning(self.warning.clone().unwrap())
-                } else {
-                    plugin_core::LintResult::Error(self.error.clone().unwrap())
-                },
+                output: self.output(),
             },
         ))
     }
 
+    /// Whether the lint has the name or tag, or is in the lints file with that name.
+    fn is_selected_by(&self, selector: &str) -> bool {
+        self.name == selector
+            || self.tags.iter().any(|tag| tag == selector)
+            || self.category.as_deref() == Some(selector)
+    }
+
     fn validate(&self) -> anyhow::Result<()> {
         if self.name.is_empty() {
             return Err(anyhow::anyhow!("Lint name is empty"));
@@ -70,17 +478,69 @@ impl LintData {
         if self.lint.is_empty() {
             return Err(anyhow::anyhow!("Lint query is empty"));
         }
-        if self.warning.is_none() && self.error.is_none() {
+        let messages = [&self.message, &self.warning, &self.error]
+            .into_iter()
+            .filter(|message| message.is_some())
+            .count();
+        if messages == 0 {
+            return Err(anyhow::anyhow!(
+                "Lint {} has no message, warning or error message",
+                self.name
+            ));
+        }
+        if messages > 1 {
+            return Err(anyhow::anyhow!(
+                "Lint {} has more than one of message, warning and error message",
+                self.name
+            ));
+        }
+        if self.severity.is_some() && self.message.is_none() {
             return Err(anyhow::anyhow!(
-                "Lint {} has no warning or error message",
+                "Lint {} has a severity but no message",
                 self.name
             ));
         }
-        if self.warning.is_some() && self.error.is_some() {
+        self.expect.validate(&self.name)?;
+        if let Some(compare) = &self.compare {
+            if self.compared_lint.is_none() {
+                return Err(anyhow::anyhow!(
+                    "Lint {} has a compare block but no compared lint",
+                    self.name
+                ));
+            }
+            if let Err(e) = compare.compile_normalizers() {
+                return Err(anyhow::anyhow!(
+                    "Lint {} has an invalid normalizer: {}",
+                    self.name,
+                    e
+                ));
+            }
+        }
+        if self.compared_plugin.is_some() && self.compared_lint.is_none() {
+            return Err(anyhow::anyhow!(
+                "Lint {} has a compared plugin but no compared lint",
+                self.name
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks that the plugins the lint runs with are configured.
+    fn validate_plugins(&self, plugin_names: &[&str]) -> anyhow::Result<()> {
+        let Some(plugin) = &self.plugin else {
             return Err(anyhow::anyhow!(
-                "Lint {} has both warning and error message",
+                "Lint {} does not specify which plugin to run with",
                 self.name
             ));
+        };
+        for plugin in std::iter::once(plugin).chain(&self.compared_plugin) {
+            if !plugin_names.contains(&plugin.as_str()) {
+                return Err(anyhow::anyhow!(
+                    "Lint {} uses the {} plugin which is not configured",
+                    self.name,
+                    plugin
+                ));
+            }
         }
         Ok(())
     }
@@ -97,44 +557,338 @@ impl LintData {
     }
 }
 
-pub fn wasm_main(config: PathBuf) -> anyhow::Result<()> {
-    let args: CliPluginConfig = serde_yaml::from_str(std::fs::read_to_string(config)?.as_str())?;
-    let plugin_data = PluginData::from_cli_plugin_config(args)?;
-    let lints = merge_lints(&plugin_data)?;
-    let wasm = match plugin_data.plugin {
-        PluginLocation::Path(path) => Wasm::file(path),
-        PluginLocation::Url(url) => Wasm::url(url),
+/// How the lints are run and reported.
+pub struct LintOptions {
+    pub format: ReportFormat,
+    /// File to write the report to. Defaults to stdout.
+    pub output: Option<PathBuf>,
+    /// Baseline file with rows that are not reported.
+    pub baseline: Option<PathBuf>,
+    /// File to write the rows that fail the lints to as a baseline.
+    pub write_baseline: Option<PathBuf>,
+    /// Number of instances of each plugin to run the lints with in parallel.
+    pub jobs: usize,
+    /// Lowest severity of the lints that fail the linter when they fail.
+    pub fail_on: Severity,
+    /// Number of rows that can fail warning lints before the linter fails.
+    pub max_warnings: Option<usize>,
+}
+
+pub fn wasm_main(
+    config: LinterConfig,
+    cache: &RemoteCache,
+    options: &LintOptions,
+) -> anyhow::Result<()> {
+    let linter_data = LinterData::from_linter_config(config)?;
+    let lints = linter_lints(&linter_data, cache)?;
+    let start = Instant::now();
+    let mut plugin_sets = load_plugin_sets(&linter_data.plugins, cache, options.jobs)?;
+    let outcomes = lint_and_report(&linter_data, &lints, &mut plugin_sets, options, start)?;
+    let warnings = warning_count(&outcomes);
+    if let Some(max_warnings) = options.max_warnings
+        && warnings > max_warnings
+    {
+        eprintln!(
+            "{} rows failed warning lints, more than the {} allowed",
+            warnings, max_warnings
+        );
+    }
+    if !lints_passed(&outcomes, options.fail_on, options.max_warnings) {
+        return Err(anyhow::anyhow!("Linting failed"));
+    }
+    Ok(())
+}
+
+/// Validates the lint queries, runs the lints with the loaded plugins and removes the suppressed
+/// rows from the outcomes.
+pub fn lint_outcomes(
+    lints: &[LintData],
+    plugin_sets: &mut [HashMap<String, Plugin>],
+) -> anyhow::Result<Vec<LintOutcome>> {
+    validate_queries(lints, &mut plugin_sets[0])?;
+    let suppressions = load_suppressions(&mut plugin_sets[0])?;
+    let mut outcomes = run_lints(lints, plugin_sets);
+    apply_suppressions(&suppressions, &mut outcomes);
+    Ok(outcomes)
+}
+
+/// Runs the lints with the loaded plugins, applies the suppressions and the baseline and writes the
+/// report.
+pub fn lint_and_report(
+    linter_data: &LinterData,
+    lints: &[LintData],
+    plugin_sets: &mut [HashMap<String, Plugin>],
+    options: &LintOptions,
+    start: Instant,
+) -> anyhow::Result<Vec<LintOutcome>> {
+    let mut outcomes = lint_outcomes(lints, plugin_sets)?;
+    let baseline = if let Some(path) = &options.write_baseline {
+        let baseline = Baseline::from_outcomes(&outcomes);
+        baseline.write(path)?;
+        info!("Wrote the baseline to {}", path.display());
+        Some(baseline)
+    } else if let Some(path) = &options.baseline {
+        Some(Baseline::read(path)?)
+    } else {
+        None
+    };
+    if let Some(baseline) = baseline {
+        baseline.apply(&mut outcomes);
+    }
+    // Only the SARIF report shows where the rows came from.
+    let locations = if options.format == ReportFormat::Sarif {
+        plugin_locations(&mut plugin_sets[0])
+    } else {
+        HashMap::new()
+    };
+    let report = LintReport {
+        plugins: &linter_data.plugins,
+        outcomes: &outcomes,
+        locations: &locations,
+        duration: start.elapsed(),
+        passed: lints_passed(&outcomes, options.fail_on, options.max_warnings),
+        fail_on: options.fail_on,
+        max_warnings: options.max_warnings,
+    };
+    write_report(options.format, &report, options.output.as_deref())?;
+    Ok(outcomes)
+}
+
+/// Merges the lints of every plugin with the shared lints, sets the plugin each lint runs with,
+/// applies the severity overrides and leaves out the lints that are off.
+pub fn linter_lints(
+    linter_data: &LinterData,
+    cache: &RemoteCache,
+) -> anyhow::Result<Vec<LintData>> {
+    let mut lints = configured_lints(linter_data, &linter_data.filter, cache)?;
+    lints.retain(|lint| {
+        let on = lint.severity() != Severity::Off;
+        if !on {
+            debug!("Lint {} is off", lint.name);
+        }
+        on
+    });
+
+    let plugin_names: Vec<&str> = linter_data
+        .plugins
+        .iter()
+        .map(|plugin| plugin.name.as_str())
+        .collect();
+    let mut valid = true;
+    for lint in &lints {
+        if let Err(e) = lint.validate_plugins(&plugin_names) {
+            eprintln!("{}", e);
+            valid = false;
+        }
+    }
+    if !valid {
+        return Err(anyhow::anyhow!("Lint config is not valid"));
+    }
+    Ok(lints)
+}
+
+/// Merges the lints of every plugin with the shared lints that the filter selects, sets the plugin
+/// each lint runs with and applies the severity overrides, keeping the lints that are off.
+pub fn configured_lints(
+    linter_data: &LinterData,
+    filter: &LintFilter,
+    cache: &RemoteCache,
+) -> anyhow::Result<Vec<LintData>> {
+    let mut lints = vec![];
+    for plugin_data in &linter_data.plugins {
+        let mut plugin_lints =
+            merge_lints(&plugin_data.lints_paths, &plugin_data.urls, filter, cache)?;
+        for lint in plugin_lints.iter_mut() {
+            lint.plugin.get_or_insert_with(|| plugin_data.name.clone());
+        }
+        lints.extend(plugin_lints);
+    }
+    let mut shared_lints = merge_lints(&linter_data.lints_paths, &linter_data.urls, filter, cache)?;
+    if let [plugin_data] = linter_data.plugins.as_slice() {
+        for lint in shared_lints.iter_mut() {
+            lint.plugin.get_or_insert_with(|| plugin_data.name.clone());
+        }
+    }
+    lints.extend(shared_lints);
+
+    for (name, severity) in &linter_data.severity_overrides {
+        let mut found = false;
+        for lint in lints.iter_mut().filter(|lint| &lint.name == name) {
+            lint.set_severity(*severity);
+            found = true;
+        }
+        if !found {
+            warn!("Severity override for {} does not match any lint", name);
+        }
+    }
+    Ok(lints)
+}
+
+/// Loads the plugin without initializing it, which is enough to call exports such as `schema`
+/// that do not need the linted files. Plugins loaded from a url go through the cache and pinned
+/// plugins are checked against their sha256 digest.
+pub fn instantiate_plugin(plugin_data: &PluginData, cache: &RemoteCache) -> anyhow::Result<Plugin> {
+    let wasm = match (&plugin_data.plugin, &plugin_data.sha256) {
+        (PluginLocation::Path(path), None) => Wasm::file(path),
+        (PluginLocation::Path(path), Some(sha256)) => {
+            let contents = std::fs::read(path)?;
+            verify_sha256(&contents, sha256, &path.display().to_string())?;
+            Wasm::data(contents)
+        }
+        (PluginLocation::Url(url), sha256) => Wasm::data(cache.fetch(url, sha256.as_deref())?),
     };
-    let manifest = Manifest::new([wasm]).with_allowed_path(
-        plugin_data
-            .directory
-            .into_os_string()
-            .into_string()
-            .unwrap(),
-        "contents",
+    Plugin::new(Manifest::new([wasm]), [], true)
+}
+
+/// Exports every plugin needs for the linter to run its lints.
+const REQUIRED_EXPORTS: [&str; 4] = ["metadata", "requested_files", "new", "lint_single"];
+
+/// Checks that the plugin has the required exports and was built with the plugin API version of
+/// the linter, and returns its metadata.
+pub fn plugin_metadata(
+    plugin_data: &PluginData,
+    plugin: &mut Plugin,
+) -> anyhow::Result<PluginMetadata> {
+    let missing: Vec<&str> = REQUIRED_EXPORTS
+        .into_iter()
+        .filter(|export| !plugin.function_exists(export))
+        .collect();
+    if !missing.is_empty() {
+        eprintln!(
+            "The {} plugin does not export {}. It may have been built for an older version of the linter.",
+            plugin_data.name,
+            missing.join(", ")
+        );
+        return Err(anyhow::anyhow!("Plugin is not compatible"));
+    }
+    let metadata = plugin
+        .call::<Option<&str>, Json<PluginMetadata>>("metadata", None)
+        .map_err(|e| {
+            eprintln!(
+                "Failed to read the metadata of the {} plugin: {:?}",
+                plugin_data.name, e
+            );
+            anyhow::anyhow!("Plugin is not compatible")
+        })?
+        .0;
+    check_compatibility(&plugin_data.name, &metadata)?;
+    Ok(metadata)
+}
+
+fn check_compatibility(plugin_name: &str, metadata: &PluginMetadata) -> anyhow::Result<()> {
+    if metadata.api_version != PLUGIN_API_VERSION {
+        eprintln!(
+            "The {} plugin ({} {}) uses plugin API version {} but this linter supports version {}. {}",
+            plugin_name,
+            metadata.name,
+            metadata.version,
+            metadata.api_version,
+            PLUGIN_API_VERSION,
+            if metadata.api_version < PLUGIN_API_VERSION {
+                "Update the plugin to a version built for this linter."
+            } else {
+                "Update the linter to use this plugin."
+            }
+        );
+        return Err(anyhow::anyhow!("Plugin is not compatible"));
+    }
+    Ok(())
+}
+
+/// Loads the plugin, checks that it is compatible and initializes it with the files it requested
+/// from its directory.
+pub fn load_plugin(plugin_data: &PluginData, cache: &RemoteCache) -> anyhow::Result<Plugin> {
+    let (mut plugin, requested) = load_compatible_plugin(plugin_data, cache)?;
+    let files = collect_plugin_files(plugin_data, &requested)?;
+    initialize_plugin(plugin_data, &mut plugin, &files)?;
+    Ok(plugin)
+}
+
+/// Loads the plugin, checks that it is compatible and returns it with the files it requests.
+fn load_compatible_plugin(
+    plugin_data: &PluginData,
+    cache: &RemoteCache,
+) -> anyhow::Result<(Plugin, Files)> {
+    let mut plugin = instantiate_plugin(plugin_data, cache)?;
+    let metadata = plugin_metadata(plugin_data, &mut plugin)?;
+    debug!(
+        "Loaded the {} plugin from {}: {} {} ({}), plugin API version {}, file types: {}",
+        plugin_data.name,
+        plugin_data.plugin,
+        metadata.name,
+        metadata.version,
+        metadata.description,
+        metadata.api_version,
+        metadata.file_types.join(", ")
+    );
+    let requested = plugin
+        .call::<Option<&str>, Json<Files>>("requested_files", None)?
+        .0;
+    Ok((plugin, requested))
+}
+
+/// Reads the files the plugin requested from its directory.
+fn collect_plugin_files(
+    plugin_data: &PluginData,
+    requested: &Files,
+) -> anyhow::Result<AvailableFiles> {
+    let files = collect_files(&plugin_data.directory, requested)?;
+    debug!(
+        "Giving {} files from {} to the {} plugin",
+        files.files.len(),
+        plugin_data.directory.display(),
+        plugin_data.name
     );
-    let mut plugin = Plugin::new(manifest, [], true).unwrap();
-    let res = plugin.call::<Option<&str>, ()>("new", None);
+    Ok(files)
+}
+
+/// Initializes the plugin with the files from its directory.
+fn initialize_plugin(
+    plugin_data: &PluginData,
+    plugin: &mut Plugin,
+    files: &AvailableFiles,
+) -> anyhow::Result<()> {
+    let res = plugin.call::<Json<&AvailableFiles>, ()>("new", Json(files));
     if res.is_err() {
-        eprintln!("Failed to initialize plugin: {:?}", res.err().unwrap());
+        eprintln!(
+            "Failed to initialize the {} plugin: {:?}",
+            plugin_data.name,
+            res.err().unwrap()
+        );
         return Err(anyhow::anyhow!("Failed to initialize plugin"));
     }
-    run_lints(lints, plugin)?;
     Ok(())
 }
 
-pub fn merge_lints(plugin: &PluginData) -> anyhow::Result<Vec<LintData>> {
-    let mut lints = vec![];
-    for lints_path in &plugin.lints_paths {
+/// Reads the lints from the lints files and keeps the lints selected by the filter.
+pub fn merge_lints(
+    lints_paths: &[PathBuf],
+    urls: &[RemoteUrl],
+    filter: &LintFilter,
+    cache: &RemoteCache,
+) -> anyhow::Result<Vec<LintData>> {
+    let mut lint_files = vec![];
+    for lints_path in lints_paths {
         let lints_file = std::fs::read_to_string(lints_path)?;
         let file_lints: LintConfig = serde_yaml::from_str(&lints_file)?;
-        lints.extend(file_lints.lints);
+        lint_files.push(file_lints);
     }
 
-    for url in &plugin.urls {
-        let lints_file = reqwest::blocking::get(url)?.text()?;
-        let file_lints: LintConfig = serde_json::from_str(&lints_file)?;
-        lints.extend(file_lints.lints);
+    for url in urls {
+        let lints_file = cache.fetch(url.url(), url.sha256())?;
+        let file_lints: LintConfig = serde_json::from_slice(&lints_file)?;
+        lint_files.push(file_lints);
+    }
+    let mut lints = vec![];
+    for file_lints in lint_files {
+        for mut lint in file_lints.lints {
+            lint.category.clone_from(&file_lints.name);
+            if filter_selects(filter, &lint) {
+                lints.push(lint);
+            } else {
+                debug!("Lint {} is not selected", lint.name);
+            }
+        }
     }
     let mut valid = true;
     for lint in &lints {
@@ -149,73 +903,690 @@ pub fn merge_lints(plugin: &PluginData) -> anyhow::Result<Vec<LintData>> {
     Ok(lints)
 }
 
-fn run_lints(lints: Vec<LintData>, mut plugin: Plugin) -> anyhow::Result<()> {
-    let mut passes = true;
+/// Whether the lint matches one of the `only` selectors, if there are any, and none of the `skip`
+/// selectors.
+pub fn filter_selects(filter: &LintFilter, lint: &LintData) -> bool {
+    (filter.only.is_empty() || filter.only.iter().any(|only| lint.is_selected_by(only)))
+        && !filter.skip.iter().any(|skip| lint.is_selected_by(skip))
+}
+
+/// Runs the lint with the named plugin and returns the resulting rows.
+fn lint_single(
+    plugins: &mut HashMap<String, Plugin>,
+    plugin_name: &str,
+    lint: plugin_core::Lint,
+) -> anyhow::Result<Vec<serde_json::Value>> {
+    let plugin = plugins
+        .get_mut(plugin_name)
+        .ok_or_else(|| anyhow::anyhow!("The {} plugin is not loaded", plugin_name))?;
+    call_lint_single(plugin, lint)
+}
+
+/// Calls the plugin's `lint_single` export and returns the resulting rows.
+pub fn call_lint_single(
+    plugin: &mut Plugin,
+    lint: plugin_core::Lint,
+) -> anyhow::Result<Vec<serde_json::Value>> {
+    let result = plugin.call::<Json<plugin_core::Lint>, String>("lint_single", Json(lint))?;
+    Ok(serde_json::from_str(&result)?)
+}
+
+/// Parses the lint and compared lint queries with the schema of the plugin that runs them, so
+/// that every invalid query is reported before any lint runs. Plugins without a `schema` export
+/// are not validated.
+fn validate_queries(
+    lints: &[LintData],
+    plugins: &mut HashMap<String, Plugin>,
+) -> anyhow::Result<()> {
+    let mut schemas = HashMap::new();
+    for (name, plugin) in plugins.iter_mut() {
+        if let Some(schema) = load_schema(plugin)? {
+            schemas.insert(name.clone(), schema);
+        }
+    }
+    let mut valid = true;
     for lint in lints {
-        if lint.compared_lint.is_some() {
-            let (main_lint, compared_lint) = lint.convert_to_plugin_lint_with_compared().unwrap();
-            let result =
-                plugin.call::<Json<plugin_core::Lint>, String>("lint_single", Json(main_lint))?;
-            let mut invalid_result: Vec<serde_json::Value> = Vec::new();
-            let lint_results: Vec<serde_json::Value> = serde_json::from_str(&result)?;
-            let result = plugin
-                .call::<Json<plugin_core::Lint>, String>("lint_single", Json(compared_lint))?;
-            let compared_lint_results: Vec<serde_json::Value> = serde_json::from_str(&result)?;
-            for result in &lint_results {
-                if !compared_lint_results.contains(result) {
-                    invalid_result.push(result.clone());
-                }
-            }
-            for result in compared_lint_results {
-                if !lint_results.contains(&result) {
-                    invalid_result.push(result.clone());
-                }
+        let plugin_name = lint
+            .plugin
+            .as_deref()
+            .expect("plugin is set when merging the lints");
+        let compared_plugin_name = lint.compared_plugin.as_deref().unwrap_or(plugin_name);
+        let queries = std::iter::once(("lint", plugin_name, lint.lint.as_str())).chain(
+            lint.compared_lint
+                .as_deref()
+                .map(|query| ("compared_lint", compared_plugin_name, query)),
+        );
+        for (field, plugin_name, query) in queries {
+            let Some(schema) = schemas.get(plugin_name) else {
+                continue;
+            };
+            for err in query_errors(schema, query) {
+                eprintln!("Lint {} has an invalid {}: {}", lint.name, field, err);
+                valid = false;
             }
-            if !invalid_result.is_empty() {
-                match (lint.warning, lint.error) {
-                    (None, Some(err)) => {
-                        error!("{}", err);
-                        passes = false;
-                    }
-                    (Some(warn), None) => {
-                        warn!("{}", warn);
-                    }
-                    _ => unreachable!("Lint has both or no warning and error message"),
+        }
+    }
+    if !valid {
+        return Err(anyhow::anyhow!("Lint queries are not valid"));
+    }
+    Ok(())
+}
+
+/// Gets the locations of the blocks of the linted sources from every plugin that has the
+/// `locations` export. A plugin that fails to return its locations is left out.
+pub fn plugin_locations(
+    plugins: &mut HashMap<String, Plugin>,
+) -> HashMap<String, Vec<SourceLocation>> {
+    let mut locations = HashMap::new();
+    for (name, plugin) in plugins.iter_mut() {
+        if plugin.function_exists("locations") {
+            match plugin.call::<Option<&str>, Json<Vec<SourceLocation>>>("locations", None) {
+                Ok(Json(plugin_locations)) => {
+                    locations.insert(name.clone(), plugin_locations);
                 }
-                println!("{}", serde_json::to_string_pretty(&invalid_result).unwrap());
+                Err(e) => eprintln!(
+                    "Failed to get the locations from the {} plugin: {}",
+                    name, e
+                ),
             }
-        } else {
-            let result = plugin.call::<Json<plugin_core::Lint>, String>(
-                "lint_single",
-                Json(lint.convert_to_plugin_lint().unwrap()),
-            );
-            match result {
-                Ok(result) => {
-                    let lint_results: Vec<serde_json::Value> = serde_json::from_str(&result)?;
-                    if !lint_results.is_empty() {
-                        match (lint.warning, lint.error) {
-                            (None, Some(err)) => {
-                                error!("{}", err);
-                                passes = false;
-                            }
-                            (Some(warn), None) => {
-                                warn!("{}", warn);
-                            }
-                            _ => unreachable!("Lint has both or no warning and error message"),
-                        }
-                        println!("{}", serde_json::to_string_pretty(&lint_results).unwrap());
+        }
+    }
+    locations
+}
+
+/// Gets the blocks with ignored lints from every plugin that has the `suppressions` export.
+fn load_suppressions(
+    plugins: &mut HashMap<String, Plugin>,
+) -> anyhow::Result<Vec<plugin_core::Suppression>> {
+    let mut suppressions = Vec::new();
+    for plugin in plugins.values_mut() {
+        if plugin.function_exists("suppressions") {
+            let Json(plugin_suppressions) = plugin
+                .call::<Option<&str>, Json<Vec<plugin_core::Suppression>>>("suppressions", None)?;
+            suppressions.extend(plugin_suppressions);
+        }
+    }
+    Ok(suppressions)
+}
+
+/// Removes the rows of blocks that ignore the lint from the outcomes.
+fn apply_suppressions(suppressions: &[plugin_core::Suppression], outcomes: &mut [LintOutcome]) {
+    for outcome in outcomes.iter_mut() {
+        let rows_count = outcome.rows.len();
+        outcome.rows.retain(|row| {
+            !suppressions
+                .iter()
+                .any(|suppression| suppression.matches(&outcome.name, row))
+        });
+        outcome.suppressed = rows_count - outcome.rows.len();
+    }
+}
+
+/// Loads a set of the plugins for each job.
+fn load_plugin_sets(
+    plugins_data: &[PluginData],
+    cache: &RemoteCache,
+    jobs: usize,
+) -> anyhow::Result<Vec<HashMap<String, Plugin>>> {
+    if jobs > 1 {
+        debug!("Loading {} instances of each plugin", jobs);
+    }
+    let mut plugin_sets: Vec<HashMap<String, Plugin>> = (0..jobs).map(|_| HashMap::new()).collect();
+    for plugin_data in plugins_data {
+        load_plugin_into_sets(plugin_data, cache, &mut plugin_sets)?;
+    }
+    Ok(plugin_sets)
+}
+
+/// Loads an instance of the plugin into each set of plugins, replacing the loaded instances, and
+/// returns the files the plugin requests. The first instance is loaded before the others so that a
+/// plugin loaded from a url is only fetched once and the requested files are only read once, and
+/// the others are loaded in parallel with the same files.
+pub fn load_plugin_into_sets(
+    plugin_data: &PluginData,
+    cache: &RemoteCache,
+    plugin_sets: &mut [HashMap<String, Plugin>],
+) -> anyhow::Result<Files> {
+    let Some((first, others)) = plugin_sets.split_first_mut() else {
+        return Ok(Files::default());
+    };
+    let (mut plugin, requested) = load_compatible_plugin(plugin_data, cache)?;
+    let files = collect_plugin_files(plugin_data, &requested)?;
+    initialize_plugin(plugin_data, &mut plugin, &files)?;
+    first.insert(plugin_data.name.clone(), plugin);
+    let files = &files;
+    std::thread::scope(|scope| -> anyhow::Result<()> {
+        let handles: Vec<_> = others
+            .iter_mut()
+            .map(|plugins| {
+                scope.spawn(move || -> anyhow::Result<()> {
+                    let (mut plugin, _) = load_compatible_plugin(plugin_data, cache)?;
+                    initialize_plugin(plugin_data, &mut plugin, files)?;
+                    plugins.insert(plugin_data.name.clone(), plugin);
+                    Ok(())
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().expect("loading the plugin panicked")?;
+        }
+        Ok(())
+    })?;
+    Ok(requested)
+}
+
+/// Runs the lints against their plugins and returns the outcome of each lint, in the order of the
+/// lints. Each set of plugins runs the next lint that has not been started on its own thread.
+fn run_lints(lints: &[LintData], plugin_sets: &mut [HashMap<String, Plugin>]) -> Vec<LintOutcome> {
+    let next_lint = AtomicUsize::new(0);
+    let mut outcomes: Vec<(usize, LintOutcome)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = plugin_sets
+            .iter_mut()
+            .map(|plugins| {
+                let next_lint = &next_lint;
+                scope.spawn(move || {
+                    let mut outcomes = Vec::new();
+                    loop {
+                        let index = next_lint.fetch_add(1, Ordering::Relaxed);
+                        let Some(lint) = lints.get(index) else {
+                            break;
+                        };
+                        outcomes.push((index, run_lint(lint, plugins)));
                     }
-                }
-                Err(err) => {
-                    error!("Error in the plugin running lint: {}", lint.name);
-                    error!("{}", err);
-                    passes = false;
-                }
-            }
+                    outcomes
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("running the lints panicked"))
+            .collect()
+    });
+    outcomes.sort_by_key(|(index, _)| *index);
+    outcomes.into_iter().map(|(_, outcome)| outcome).collect()
+}
+
+/// Runs the lint, and its compared lint, and returns its outcome.
+fn run_lint(lint: &LintData, plugins: &mut HashMap<String, Plugin>) -> LintOutcome {
+    let plugin_name = lint
+        .plugin
+        .clone()
+        .expect("plugin is set when merging the lints");
+    let start = Instant::now();
+    let result = if lint.compared_lint.is_some() {
+        let compared_plugin_name = lint
+            .compared_plugin
+            .clone()
+            .unwrap_or_else(|| plugin_name.clone());
+        let (main_lint, compared_lint) = lint.convert_to_plugin_lint_with_compared().unwrap();
+        lint_single(plugins, &plugin_name, main_lint).and_then(|lint_results| {
+            let compared_lint_results = lint_single(plugins, &compared_plugin_name, compared_lint)?;
+            lint.compare
+                .clone()
+                .unwrap_or_default()
+                .unmatched_rows(&lint_results, &compared_lint_results)
+        })
+    } else {
+        lint_single(
+            plugins,
+            &plugin_name,
+            lint.convert_to_plugin_lint().unwrap(),
+        )
+    };
+    let (rows, unmet_expectation, error) = match result {
+        Ok(rows) => {
+            let (rows, unmet_expectation) = lint.expect.check(rows);
+            (rows, unmet_expectation, None)
         }
+        Err(err) => (Vec::new(), None, Some(err.to_string())),
+    };
+    LintOutcome {
+        name: lint.name.clone(),
+        plugin: plugin_name,
+        compared_plugin: lint.compared_plugin.clone(),
+        output: lint.output(),
+        docs: lint.docs.clone(),
+        args: lint.args.clone(),
+        rows,
+        unmet_expectation,
+        error,
+        duration: start.elapsed(),
+        suppressed: 0,
+        baselined: 0,
+        fixed: Vec::new(),
     }
-    if !passes {
-        return Err(anyhow::anyhow!("Linting failed"));
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn metadata(api_version: u32) -> PluginMetadata {
+        PluginMetadata {
+            name: "openapi".to_string(),
+            version: "0.1.0".to_string(),
+            api_version,
+            description: "Lints OpenAPI documents".to_string(),
+            file_types: vec!["yaml".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_check_compatibility() {
+        assert!(check_compatibility("openapi", &metadata(PLUGIN_API_VERSION)).is_ok());
+        assert!(check_compatibility("openapi", &metadata(PLUGIN_API_VERSION + 1)).is_err());
+        assert!(check_compatibility("openapi", &metadata(0)).is_err());
+    }
+
+    fn cross_plugin_lint() -> LintData {
+        LintData {
+            name: "Endpoints should be declared".to_string(),
+            lint: "{ Paths { path @output } }".to_string(),
+            error: Some("Some endpoints are not declared".to_string()),
+            compared_lint: Some("{ Lambda { name @output(name: \"path\") } }".to_string()),
+            plugin: Some("openapi".to_string()),
+            compared_plugin: Some("terraform".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_plugins() {
+        let lint = cross_plugin_lint();
+        assert!(lint.validate().is_ok());
+        assert!(lint.validate_plugins(&["openapi", "terraform"]).is_ok());
+        assert!(lint.validate_plugins(&["openapi"]).is_err());
+    }
+
+    #[test]
+    fn test_validate_compared_plugin_without_compared_lint() {
+        let lint = LintData {
+            compared_lint: None,
+            ..cross_plugin_lint()
+        };
+        assert!(lint.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_no_plugin() {
+        let lint = LintData {
+            plugin: None,
+            ..cross_plugin_lint()
+        };
+        assert!(lint.validate_plugins(&["openapi", "terraform"]).is_err());
+    }
+
+    #[test]
+    fn test_run_lints_keeps_the_lints_order() {
+        let lints: Vec<LintData> = (0..10)
+            .map(|i| LintData {
+                name: format!("Lint {}", i),
+                error: Some(format!("Lint {} failed", i)),
+                plugin: Some(if i % 2 == 0 { "openapi" } else { "terraform" }.to_string()),
+                ..Default::default()
+            })
+            .collect();
+        let mut plugin_sets = vec![HashMap::new(), HashMap::new(), HashMap::new()];
+        let outcomes = run_lints(&lints, &mut plugin_sets);
+        let names: Vec<&str> = outcomes
+            .iter()
+            .map(|outcome| outcome.name.as_str())
+            .collect();
+        let expected: Vec<&str> = lints.iter().map(|lint| lint.name.as_str()).collect();
+        assert_eq!(names, expected);
+        assert_eq!(outcomes[1].plugin, "terraform");
+        assert_eq!(
+            outcomes[1].error.as_deref(),
+            Some("The terraform plugin is not loaded")
+        );
+    }
+
+    #[test]
+    fn test_apply_suppressions() {
+        let suppression = plugin_core::Suppression {
+            lints: vec!["No DELETE method".to_string()],
+            fields: json!({"path": "/pets/{id}", "method": "DELETE"})
+                .as_object()
+                .unwrap()
+                .clone(),
+        };
+        let mut outcomes = vec![crate::report::tests::outcome(
+            plugin_core::LintResult::Error("No DELETE method allowed".to_string()),
+            vec![
+                json!({"path": "/pets/{id}", "method": "DELETE", "summary": "Delete a pet"}),
+                json!({"path": "/pets/{id}"}),
+                json!({"path": "/pets/{id}", "method": "GET"}),
+                json!({"method": "DELETE"}),
+                json!({"path": "/users/{id}", "method": "DELETE"}),
+                json!({"lambda_key": "pets"}),
+            ],
+        )];
+        apply_suppressions(&[suppression], &mut outcomes);
+        assert_eq!(
+            outcomes[0].rows,
+            vec![
+                json!({"path": "/pets/{id}"}),
+                json!({"path": "/pets/{id}", "method": "GET"}),
+                json!({"method": "DELETE"}),
+                json!({"path": "/users/{id}", "method": "DELETE"}),
+                json!({"lambda_key": "pets"}),
+            ]
+        );
+        assert_eq!(outcomes[0].suppressed, 1);
+    }
+
+    #[test]
+    fn test_set_severity() {
+        let mut lint = cross_plugin_lint();
+        assert_eq!(lint.severity(), Severity::Error);
+        lint.set_severity(Severity::Warning);
+        assert_eq!(lint.severity(), Severity::Warning);
+        assert_eq!(lint.message(), "Some endpoints are not declared");
+        assert!(lint.validate().is_ok());
+        lint.set_severity(Severity::Info);
+        assert!(matches!(
+            lint.output(),
+            plugin_core::LintResult::Info(message) if message == "Some endpoints are not declared"
+        ));
+    }
+
+    #[test]
+    fn test_validate_messages() {
+        let lint = LintData {
+            error: None,
+            message: Some("Some endpoints are not declared".to_string()),
+            severity: Some(Severity::Warning),
+            ..cross_plugin_lint()
+        };
+        assert!(lint.validate().is_ok());
+        assert_eq!(lint.severity(), Severity::Warning);
+        let lint = LintData {
+            warning: Some("Some endpoints are not declared".to_string()),
+            ..cross_plugin_lint()
+        };
+        assert!(lint.validate().is_err());
+        let lint = LintData {
+            severity: Some(Severity::Warning),
+            ..cross_plugin_lint()
+        };
+        assert!(lint.validate().is_err());
+        let lint = LintData {
+            error: None,
+            ..cross_plugin_lint()
+        };
+        assert!(lint.validate().is_err());
+    }
+
+    #[test]
+    fn test_expect_check() {
+        let rows = vec![json!({"name": "api"}), json!({"name": "worker"})];
+        assert_eq!(Expect::Empty.check(rows.clone()), (rows.clone(), None));
+        assert_eq!(Expect::NonEmpty.check(rows.clone()), (Vec::new(), None));
+        assert_eq!(
+            Expect::NonEmpty.check(Vec::new()),
+            (
+                Vec::new(),
+                Some("expected at least 1 row, got 0".to_string())
+            )
+        );
+        let count = |min, max| Expect::Count { min, max };
+        assert_eq!(count(Some(1), Some(2)).check(rows.clone()).1, None);
+        assert_eq!(
+            count(None, Some(1)).check(rows.clone()),
+            (
+                rows.clone(),
+                Some("expected at most 1 rows, got 2".to_string())
+            )
+        );
+        assert_eq!(
+            count(Some(3), None).check(rows.clone()),
+            (
+                Vec::new(),
+                Some("expected at least 3 rows, got 2".to_string())
+            )
+        );
+        let expected = vec![json!({"name": "api"}), json!({"name": "db"})];
+        assert_eq!(
+            Expect::Equal(expected).check(rows.clone()),
+            (
+                vec![json!({"name": "worker"}), json!({"name": "db"})],
+                Some("1 rows were not expected and 1 expected rows are missing".to_string())
+            )
+        );
+        assert_eq!(
+            Expect::Equal(rows.iter().rev().cloned().collect()).check(rows),
+            (Vec::new(), None)
+        );
+    }
+
+    #[test]
+    fn test_expect_from_yaml() {
+        let lints: LintConfig = serde_yaml::from_str(
+            r#"
+lints:
+  - name: Backends
+    lint: "{ Backend { name @output } }"
+    error: At least one backend must be configured
+    expect: non_empty
+  - name: Regions
+    lint: "{ Region { name @output } }"
+    error: Two or three regions are used
+    expect:
+      count:
+        min: 2
+        max: 3
+  - name: Modules
+    lint: "{ Module { name @output } }"
+    error: Only the api module is used
+    expect:
+      equal:
+        - name: api
+  - name: Lambdas
+    lint: "{ Lambda { name @output } }"
+    error: Lambdas are not allowed
+"#,
+        )
+        .unwrap();
+        let expects: Vec<&Expect> = lints.lints.iter().map(|lint| &lint.expect).collect();
+        assert_eq!(
+            expects,
+            vec![
+                &Expect::NonEmpty,
+                &Expect::Count {
+                    min: Some(2),
+                    max: Some(3)
+                },
+                &Expect::Equal(vec![json!({"name": "api"})]),
+                &Expect::Empty,
+            ]
+        );
+        let lint = LintData {
+            expect: Expect::Count {
+                min: Some(3),
+                max: Some(2),
+            },
+            ..cross_plugin_lint()
+        };
+        assert!(lint.validate().is_err());
+    }
+
+    #[test]
+    fn test_unmatched_rows() {
+        let rows = vec![
+            json!({"path": "/pets", "method": "GET"}),
+            json!({"path": "/owners", "method": "GET"}),
+        ];
+        let compared_rows = vec![
+            json!({"http_path": "/pets", "lambda": "pets"}),
+            json!({"http_path": "/users", "lambda": "users"}),
+        ];
+        let compare = Compare {
+            keys: vec!["path".to_string()],
+            field_map: BTreeMap::from([("http_path".to_string(), "path".to_string())]),
+            ..Default::default()
+        };
+        assert_eq!(
+            compare.unmatched_rows(&rows, &compared_rows).unwrap(),
+            vec![
+                json!({"path": "/owners", "method": "GET", "missing_from": "compared_lint"}),
+                json!({"http_path": "/users", "lambda": "users", "missing_from": "lint"}),
+            ]
+        );
+        let subset = Compare {
+            mode: CompareMode::Subset,
+            ..compare.clone()
+        };
+        assert_eq!(
+            subset.unmatched_rows(&rows, &compared_rows).unwrap(),
+            vec![json!({"path": "/owners", "method": "GET", "missing_from": "compared_lint"})]
+        );
+        let superset = Compare {
+            mode: CompareMode::Superset,
+            ..compare
+        };
+        assert_eq!(
+            superset.unmatched_rows(&rows, &compared_rows).unwrap(),
+            vec![json!({"http_path": "/users", "lambda": "users", "missing_from": "lint"})]
+        );
+        assert_eq!(
+            Compare::default()
+                .unmatched_rows(&rows, &rows[..1])
+                .unwrap(),
+            vec![json!({"path": "/owners", "method": "GET", "missing_from": "compared_lint"})]
+        );
+    }
+
+    #[test]
+    fn test_normalizers() {
+        let apply =
+            |normalizer: Normalizer, value: &str| normalizer.compile().unwrap().apply(value);
+        assert_eq!(apply(Normalizer::TrimQuotes, "\"GET\""), "GET");
+        assert_eq!(apply(Normalizer::TrimQuotes, "'GET'"), "GET");
+        assert_eq!(apply(Normalizer::Lowercase, "GET"), "get");
+        assert_eq!(apply(Normalizer::Uppercase, "get"), "GET");
+        assert_eq!(apply(Normalizer::StripTrailingSlash, "/pets//"), "/pets");
+        assert_eq!(apply(Normalizer::StripTrailingSlash, "/"), "/");
+        assert_eq!(
+            apply(Normalizer::PathTemplate, "/pets/{petId}/toys/{id}"),
+            "/pets/{}/toys/{}"
+        );
+        assert_eq!(
+            apply(
+                Normalizer::RegexReplace {
+                    pattern: "^/v[0-9]+".to_string(),
+                    replacement: String::new(),
+                },
+                "/v1/pets"
+            ),
+            "/pets"
+        );
+    }
+
+    #[test]
+    fn test_normalized_unmatched_rows() {
+        let rows = vec![
+            json!({"path": "/pets/{petId}", "method": "get"}),
+            json!({"path": "/owners", "method": "get"}),
+        ];
+        let compared_rows = vec![
+            json!({"http_path": "/v1/pets/{id}/", "http_method": "\"GET\""}),
+            json!({"http_path": "/v1/owners", "http_method": "\"POST\""}),
+        ];
+        let compare: Compare = serde_yaml::from_str(
+            r#"
+keys: [path, method]
+field_map:
+  http_path: path
+  http_method: method
+normalize:
+  path:
+    - strip_trailing_slash
+    - path_template
+    - regex_replace:
+        pattern: ^/v[0-9]+
+  method: [trim_quotes, uppercase]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            compare.unmatched_rows(&rows, &compared_rows).unwrap(),
+            vec![
+                json!({"path": "/owners", "method": "get", "missing_from": "compared_lint"}),
+                json!({"http_path": "/v1/owners", "http_method": "\"POST\"", "missing_from": "lint"}),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_normalizer_regex() {
+        let lint = LintData {
+            compare: Some(Compare {
+                normalize: BTreeMap::from([(
+                    "path".to_string(),
+                    vec![Normalizer::RegexReplace {
+                        pattern: "(".to_string(),
+                        replacement: String::new(),
+                    }],
+                )]),
+                ..Default::default()
+            }),
+            ..cross_plugin_lint()
+        };
+        assert!(lint.validate().is_err());
+    }
+
+    #[test]
+    fn test_compare_without_compared_lint() {
+        let lint = LintData {
+            compared_lint: None,
+            compared_plugin: None,
+            compare: Some(Compare::default()),
+            ..cross_plugin_lint()
+        };
+        assert!(lint.validate().is_err());
+    }
+
+    #[test]
+    fn test_shared_lints_file() {
+        let lints = merge_lints(
+            &[PathBuf::from("lints/endpoints.yaml")],
+            &[],
+            &LintFilter::default(),
+            &RemoteCache::new(true),
+        )
+        .unwrap();
+        assert!(lints.iter().all(|lint| {
+            lint.plugin.as_deref() == Some("openapi")
+                && lint.compared_plugin.as_deref() == Some("terraform")
+                && lint.category.as_deref() == Some("Endpoints")
+        }));
+    }
+
+    #[test]
+    fn test_filter_selects() {
+        let lint = LintData {
+            tags: vec!["api-gateway".to_string(), "security".to_string()],
+            category: Some("Endpoints".to_string()),
+            ..cross_plugin_lint()
+        };
+        let filter = |only: &[&str], skip: &[&str]| LintFilter {
+            only: only.iter().map(|s| s.to_string()).collect(),
+            skip: skip.iter().map(|s| s.to_string()).collect(),
+        };
+        assert!(filter_selects(&filter(&[], &[]), &lint));
+        assert!(filter_selects(&filter(&["security"], &[]), &lint));
+        assert!(filter_selects(&filter(&["Endpoints"], &[]), &lint));
+        assert!(filter_selects(
+            &filter(&["Endpoints should be declared"], &[]),
+            &lint
+        ));
+        assert!(!filter_selects(&filter(&["style"], &[]), &lint));
+        assert!(!filter_selects(&filter(&[], &["api-gateway"]), &lint));
+        assert!(!filter_selects(
+            &filter(&["security"], &["Endpoints"]),
+            &lint
+        ));
     }
-    Ok(())
 }
@@ -0,0 +1,378 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::{Component, Path, PathBuf},
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use extism::Plugin;
+use notify::{Event, RecursiveMode, Watcher};
+use plugin_core::Files;
+use simplelog::{error, info};
+
+use crate::{
+    cache::RemoteCache,
+    files::is_ignored,
+    plugin_config::{LinterConfig, LinterData, PluginLocation},
+    report::summary,
+    wasm_main::{lint_and_report, linter_lints, load_plugin_into_sets, LintData, LintOptions},
+};
+
+/// How long to wait for more changes before linting again.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// What has to be loaded again before the lints are run.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Changes {
+    lints: bool,
+    /// Names of the plugins whose files changed.
+    plugins: BTreeSet<String>,
+}
+
+impl Changes {
+    fn is_empty(&self) -> bool {
+        !self.lints && self.plugins.is_empty()
+    }
+
+    fn extend(&mut self, other: Changes) {
+        self.lints |= other.lints;
+        self.plugins.extend(other.plugins);
+    }
+}
+
+/// The files the lints depend on.
+#[derive(Debug, Default)]
+struct WatchedPaths {
+    lints_files: Vec<PathBuf>,
+    /// The directory or file linted by each plugin.
+    plugin_directories: Vec<(String, PathBuf)>,
+    /// The plugins that are local files.
+    plugin_files: Vec<(String, PathBuf)>,
+    /// The files each plugin requested, to leave out the changes to files it does not read.
+    requested: HashMap<String, Files>,
+    /// The report and baseline files the linter writes, which would otherwise start another lint
+    /// whenever they are written in a linted directory.
+    written_files: Vec<PathBuf>,
+}
+
+impl WatchedPaths {
+    fn new(linter_data: &LinterData, options: &LintOptions) -> Self {
+        let mut watched = Self::default();
+        for plugin_data in &linter_data.plugins {
+            watched
+                .lints_files
+                .extend(plugin_data.lints_paths.iter().map(|path| canonical(path)));
+            watched
+                .plugin_directories
+                .push((plugin_data.name.clone(), canonical(&plugin_data.directory)));
+            if let PluginLocation::Path(path) = &plugin_data.plugin {
+                watched
+                    .plugin_files
+                    .push((plugin_data.name.clone(), canonical(path)));
+            }
+        }
+        watched
+            .lints_files
+            .extend(linter_data.lints_paths.iter().map(|path| canonical(path)));
+        watched.written_files = [&options.output, &options.baseline, &options.write_baseline]
+            .into_iter()
+            .flatten()
+            .map(|path| canonical(path))
+            .collect();
+        watched
+    }
+
+    fn plugin_paths(&self) -> impl Iterator<Item = &(String, PathBuf)> {
+        self.plugin_directories.iter().chain(&self.plugin_files)
+    }
+
+    /// The paths to watch, with whether they are watched recursively. Files are watched through
+    /// their directory as editors often replace a file when saving it.
+    fn watches(&self) -> BTreeMap<PathBuf, bool> {
+        let mut watches = BTreeMap::new();
+        let paths = self
+            .lints_files
+            .iter()
+            .chain(self.plugin_paths().map(|(_, path)| path));
+        for path in paths {
+            if path.is_dir() {
+                watches.insert(path.clone(), true);
+            } else if let Some(parent) = path.parent() {
+                watches.entry(parent.to_path_buf()).or_insert(false);
+            }
+        }
+        watches
+    }
+
+    fn changes(&self, paths: &[PathBuf]) -> Changes {
+        let mut changes = Changes::default();
+        let not_requested = Files::default();
+        for path in paths {
+            if self.written_files.contains(path)
+                || path
+                    .components()
+                    .any(|c| c == Component::Normal(".git".as_ref()))
+            {
+                continue;
+            }
+            if self.lints_files.contains(path) {
+                changes.lints = true;
+            }
+            for (name, directory) in &self.plugin_directories {
+                let requested = self.requested.get(name).unwrap_or(&not_requested);
+                if path.starts_with(directory) && !is_ignored(directory, path, requested) {
+                    changes.plugins.insert(name.clone());
+                }
+            }
+            for (name, plugin_file) in &self.plugin_files {
+                if path == plugin_file {
+                    changes.plugins.insert(name.clone());
+                }
+            }
+        }
+        changes
+    }
+}
+
+/// The canonical path, or the canonical path of its parent joined with its name when the file does
+/// not exist yet, such as a report that has not been written.
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize()
+        .unwrap_or_else(|_| match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => {
+                canonical(parent).join(name)
+            }
+            (Some(_), Some(name)) => canonical(Path::new(".")).join(name),
+            _ => path.to_path_buf(),
+        })
+}
+
+/// Lints, then keeps running and lints again whenever the linted files, the plugins or the lints
+/// files change. Only the plugins whose files changed are loaded again, and the lints are only
+/// read again when a lints file changed.
+pub fn watch_main(
+    config: LinterConfig,
+    cache: &RemoteCache,
+    options: &LintOptions,
+) -> anyhow::Result<()> {
+    let linter_data = LinterData::from_linter_config(config)?;
+    let mut watched = WatchedPaths::new(&linter_data, options);
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    for (path, recursive) in watched.watches() {
+        let mode = if recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        watcher.watch(&path, mode)?;
+    }
+
+    let mut lints = Vec::new();
+    let mut plugin_sets: Vec<HashMap<String, Plugin>> =
+        (0..options.jobs).map(|_| HashMap::new()).collect();
+    let mut changes = Changes {
+        lints: true,
+        plugins: linter_data
+            .plugins
+            .iter()
+            .map(|plugin_data| plugin_data.name.clone())
+            .collect(),
+    };
+    loop {
+        let start = Instant::now();
+        changes = reload(
+            &linter_data,
+            cache,
+            &mut lints,
+            &mut plugin_sets,
+            &mut watched.requested,
+            changes,
+        );
+        if changes.is_empty() {
+            match lint_and_report(&linter_data, &lints, &mut plugin_sets, options, start) {
+                Ok(outcomes) => info!("{}", summary(&outcomes)),
+                Err(e) => error!("{}", e),
+            }
+        }
+        info!("Watching for changes");
+        loop {
+            let new_changes = watched.changes(&changed_paths(&rx)?);
+            if !new_changes.is_empty() {
+                changes.extend(new_changes);
+                break;
+            }
+        }
+    }
+}
+
+/// Waits for files to be created, changed or removed and returns their paths once no more files
+/// changed for a moment, as saving a file often changes it several times.
+fn changed_paths(rx: &mpsc::Receiver<notify::Result<Event>>) -> anyhow::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    let mut event = rx.recv()?;
+    loop {
+        match event {
+            Ok(event)
+                if event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove() =>
+            {
+                paths.extend(event.paths);
+            }
+            Ok(_) => {}
+            Err(e) => error!("Failed to watch for changes: {}", e),
+        }
+        let timeout = if paths.is_empty() {
+            Duration::MAX
+        } else {
+            DEBOUNCE
+        };
+        event = match rx.recv_timeout(timeout) {
+            Ok(event) => event,
+            Err(mpsc::RecvTimeoutError::Timeout) => return Ok(paths),
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                return Err(anyhow::anyhow!("Stopped watching for changes"));
+            }
+        };
+    }
+}
+
+/// Loads the changed lints and plugins again, keeps the files the plugins requested and returns the
+/// changes that failed to load, so that they are retried after the next change.
+fn reload(
+    linter_data: &LinterData,
+    cache: &RemoteCache,
+    lints: &mut Vec<LintData>,
+    plugin_sets: &mut [HashMap<String, Plugin>],
+    requested: &mut HashMap<String, Files>,
+    mut changes: Changes,
+) -> Changes {
+    if changes.lints {
+        match linter_lints(linter_data, cache) {
+            Ok(new_lints) => {
+                *lints = new_lints;
+                changes.lints = false;
+            }
+            Err(e) => error!("Failed to load the lints: {}", e),
+        }
+    }
+    changes.plugins.retain(|name| {
+        let plugin_data = linter_data
+            .plugins
+            .iter()
+            .find(|plugin_data| &plugin_data.name == name)
+            .expect("only configured plugins are watched");
+        match load_plugin_into_sets(plugin_data, cache, plugin_sets) {
+            Ok(files) => {
+                requested.insert(name.clone(), files);
+                false
+            }
+            Err(e) => {
+                error!("Failed to load the {} plugin: {}", name, e);
+                true
+            }
+        }
+    });
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn watched() -> WatchedPaths {
+        WatchedPaths {
+            lints_files: vec![PathBuf::from("/repo/lints/openapi.yaml")],
+            plugin_directories: vec![
+                ("openapi".to_string(), PathBuf::from("/repo/api")),
+                ("terraform".to_string(), PathBuf::from("/repo/terraform")),
+            ],
+            plugin_files: vec![(
+                "openapi".to_string(),
+                PathBuf::from("/repo/plugins/openapi.wasm"),
+            )],
+            requested: HashMap::from([(
+                "terraform".to_string(),
+                Files {
+                    extensions: vec!["tf".to_string()],
+                    ignored_dirs: vec![".terraform".to_string()],
+                },
+            )]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_changes() {
+        let watched = watched();
+        assert_eq!(
+            watched.changes(&[
+                PathBuf::from("/repo/lints/openapi.yaml"),
+                PathBuf::from("/repo/terraform/modules/main.tf"),
+            ]),
+            Changes {
+                lints: true,
+                plugins: BTreeSet::from(["terraform".to_string()]),
+            }
+        );
+        assert_eq!(
+            watched
+                .changes(&[PathBuf::from("/repo/plugins/openapi.wasm")])
+                .plugins,
+            BTreeSet::from(["openapi".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_unrelated_changes() {
+        let watched = watched();
+        assert!(watched
+            .changes(&[
+                PathBuf::from("/repo/lints/terraform.yaml"),
+                PathBuf::from("/repo/plugins/terraform.wasm"),
+                PathBuf::from("/repo/api/.git/index"),
+                PathBuf::from("/repo/terraform/.terraform/modules/main.tf"),
+            ])
+            .is_empty());
+    }
+
+    #[test]
+    fn test_written_files_are_not_changes() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = temp_dir.path().to_path_buf();
+        std::fs::create_dir_all(dir.join("target")).unwrap();
+        std::fs::write(dir.join(".gitignore"), "target/\n").unwrap();
+        let watched = WatchedPaths {
+            plugin_directories: vec![("openapi".to_string(), dir.clone())],
+            written_files: vec![dir.join("report.json"), dir.join("baseline.json")],
+            ..Default::default()
+        };
+        assert!(watched
+            .changes(&[
+                dir.join("report.json"),
+                dir.join("baseline.json"),
+                dir.join("target").join("debug").join("linter"),
+            ])
+            .is_empty());
+        assert_eq!(
+            watched.changes(&[dir.join("openapi.yaml")]).plugins,
+            BTreeSet::from(["openapi".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_watches() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        std::fs::create_dir_all(dir.join("api")).unwrap();
+        let watched = WatchedPaths {
+            lints_files: vec![dir.join("lints.yaml")],
+            plugin_directories: vec![("openapi".to_string(), dir.join("api"))],
+            plugin_files: vec![("openapi".to_string(), dir.join("openapi.wasm"))],
+            ..Default::default()
+        };
+        assert_eq!(
+            watched.watches(),
+            BTreeMap::from([(dir.to_path_buf(), false), (dir.join("api"), true)])
+        );
+    }
+}